@@ -0,0 +1,131 @@
+//! Relay for online races. Clients join a room by sending its game code as their first message.
+//! Every later message is passed on, as is, to everybody else in the same room.
+//!
+//! ```sh
+//! cargo run -p mahjong-relay -- 127.0.0.1:9001
+//! ```
+
+use std::{
+    collections::HashMap,
+    io::ErrorKind,
+    net::{TcpListener, TcpStream},
+    sync::{
+        Arc, Mutex,
+        mpsc::{self, Receiver, Sender},
+    },
+    thread,
+    time::Duration,
+};
+use tungstenite::{Error, Message, WebSocket, error::ProtocolError};
+
+const DEFAULT_ADDRESS: &str = "127.0.0.1:9001";
+
+/// How long a connection waits for a message before passing on those of others.
+const POLL: Duration = Duration::from_millis(50);
+
+/// Outgoing messages of every client, per game code.
+type Rooms = Arc<Mutex<HashMap<String, Vec<(usize, Sender<String>)>>>>;
+
+fn main() {
+    let address = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| DEFAULT_ADDRESS.to_string());
+    let listener = TcpListener::bind(&address).expect("Could not bind address!");
+    let rooms = Rooms::default();
+
+    println!("Relaying on ws://{address}");
+
+    for (id, stream) in listener.incoming().enumerate() {
+        let Ok(stream) = stream else {
+            continue;
+        };
+        let rooms = Arc::clone(&rooms);
+
+        thread::spawn(move || {
+            if let Err(error) = serve(id, stream, &rooms) {
+                println!("[{id}] {error}");
+            }
+        });
+    }
+}
+
+fn serve(
+    id: usize,
+    stream: TcpStream,
+    rooms: &Rooms,
+) -> Result<(), Error> {
+    let mut socket = tungstenite::accept(stream).map_err(|error| match error {
+        tungstenite::HandshakeError::Failure(error) => error,
+        tungstenite::HandshakeError::Interrupted(_) => Error::ConnectionClosed,
+    })?;
+
+    let code = loop {
+        match socket.read()? {
+            Message::Text(code) => break code.to_string(),
+            Message::Close(_) => return Ok(()),
+            _ => continue,
+        }
+    };
+
+    let (sender, receiver) = mpsc::channel();
+    rooms
+        .lock()
+        .unwrap()
+        .entry(code.clone())
+        .or_default()
+        .push((id, sender));
+    println!("[{id}] Joined {code}");
+
+    socket.get_ref().set_read_timeout(Some(POLL))?;
+    let result = relay(id, &code, &mut socket, &receiver, rooms);
+
+    let mut rooms = rooms.lock().unwrap();
+    if let Some(clients) = rooms.get_mut(&code) {
+        clients.retain(|(other, _)| *other != id);
+
+        if clients.is_empty() {
+            rooms.remove(&code);
+        }
+    }
+    println!("[{id}] Left {code}");
+
+    // Clients which quit without saying goodbye left all the same.
+    match result {
+        Err(
+            Error::ConnectionClosed
+            | Error::AlreadyClosed
+            | Error::Protocol(ProtocolError::ResetWithoutClosingHandshake),
+        ) => Ok(()),
+        result => result,
+    }
+}
+
+fn relay(
+    id: usize,
+    code: &str,
+    socket: &mut WebSocket<TcpStream>,
+    receiver: &Receiver<String>,
+    rooms: &Rooms,
+) -> Result<(), Error> {
+    loop {
+        match socket.read() {
+            Ok(Message::Text(text)) => {
+                let rooms = rooms.lock().unwrap();
+
+                for (_, sender) in rooms[code].iter().filter(|(other, _)| *other != id) {
+                    // A client which just left is removed from the room by its own thread.
+                    let _ = sender.send(text.to_string());
+                }
+            },
+            Ok(Message::Close(_)) => return Ok(()),
+            Ok(_) => {},
+            Err(Error::Io(error))
+                if matches!(error.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {},
+            Err(error) => return Err(error),
+        }
+
+        for text in receiver.try_iter() {
+            socket.send(Message::text(text))?;
+        }
+    }
+}
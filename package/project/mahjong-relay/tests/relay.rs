@@ -0,0 +1,96 @@
+//! Runs the relay on a free port, and races two clients in the same room through it.
+
+use std::{
+    net::{TcpListener, TcpStream},
+    process::{Child, Command},
+    thread,
+    time::{Duration, Instant},
+};
+use tungstenite::{Message, WebSocket, stream::MaybeTlsStream};
+
+/// Game code of the room, as the game sends it.
+const ROOM: &str = "rules.classic/42";
+
+/// How long the relay gets to start, and messages to arrive.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+type Client = WebSocket<MaybeTlsStream<TcpStream>>;
+
+/// Kills the relay once the test is done, whether it passed or not.
+struct Relay(Child);
+
+impl Drop for Relay {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+fn free_address() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    listener.local_addr().unwrap().to_string()
+}
+
+/// Connects to the relay at `address` and joins [ROOM], retrying until the relay listens.
+fn join(address: &str) -> Client {
+    let start = Instant::now();
+
+    loop {
+        match tungstenite::connect(format!("ws://{address}")) {
+            Ok((mut client, _)) => {
+                client.send(Message::text(ROOM)).unwrap();
+                return client;
+            },
+            Err(_) if start.elapsed() < TIMEOUT => thread::sleep(Duration::from_millis(50)),
+            Err(error) => panic!("Could not connect to the relay: {error}"),
+        }
+    }
+}
+
+/// Sends `text` from `from` until `to` receives it. The relay only passes on messages once both
+/// have joined, which the clients have no way of knowing.
+fn relayed(
+    from: &mut Client,
+    to: &mut Client,
+    text: &str,
+) {
+    if let MaybeTlsStream::Plain(stream) = to.get_ref() {
+        stream
+            .set_read_timeout(Some(Duration::from_millis(100)))
+            .unwrap();
+    }
+
+    let start = Instant::now();
+
+    while start.elapsed() < TIMEOUT {
+        from.send(Message::text(text)).unwrap();
+
+        match to.read() {
+            Ok(Message::Text(received)) => {
+                assert_eq!(received.as_str(), text);
+                return;
+            },
+            Ok(_) | Err(tungstenite::Error::Io(_)) => {},
+            Err(error) => panic!("Relay failed: {error}"),
+        }
+    }
+
+    panic!("{text} was not relayed");
+}
+
+#[test]
+fn relays_within_a_room() {
+    let address = free_address();
+    let _relay = Relay(
+        Command::new(env!("CARGO_BIN_EXE_mahjong-relay"))
+            .arg(&address)
+            .spawn()
+            .unwrap(),
+    );
+
+    let mut first = join(&address);
+    let mut second = join(&address);
+
+    relayed(&mut first, &mut second, "1");
+    relayed(&mut second, &mut first, "2");
+}
@@ -0,0 +1,5 @@
+//! Makes image variants and the web bundle of the assets, see [mahjong::plugin::variant::tool].
+
+fn main() {
+    mahjong::plugin::variant::tool::main();
+}
@@ -0,0 +1,5 @@
+//! Headless benchmark of the board generators and bot strategies, see [mahjong::sim].
+
+fn main() {
+    mahjong::sim::main();
+}
@@ -1,7 +1,6 @@
-mod plugin;
-
 use bevy::prelude::*;
+use mahjong::plugin;
 
 fn main() {
     App::new().add_plugins(plugin::Plugin).run();
-}
\ No newline at end of file
+}
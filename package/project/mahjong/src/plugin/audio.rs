@@ -0,0 +1,312 @@
+use crate::plugin::{
+    locale,
+    scene::main_menu::settings::{AppExt, Setting},
+};
+use bevy::{
+    audio::{AudioSinkPlayback, Volume},
+    prelude::*,
+    window::WindowFocused,
+};
+use std::time::Duration;
+
+pub mod asset {
+    pub const MUSIC: &str = "audio/music/background.wav";
+    pub const CLICK: &str = "audio/sfx/click.wav";
+    pub const MATCH: &str = "audio/sfx/match.wav";
+    pub const MISMATCH: &str = "audio/sfx/mismatch.wav";
+    pub const UNDO: &str = "audio/sfx/undo.wav";
+    pub const SHUFFLE: &str = "audio/sfx/shuffle.wav";
+    pub const VICTORY: &str = "audio/sfx/victory.wav";
+    pub const DEFEAT: &str = "audio/sfx/defeat.wav";
+}
+
+/// Step used when adjusting a volume from the settings panel.
+const VOLUME_STEP: f32 = 0.1;
+
+/// A sound effect entity which has not started playing by then is assumed to never do so, e.g.
+/// when there is no audio device, and is despawned.
+const SFX_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub struct Plugin;
+
+impl bevy::prelude::Plugin for Plugin {
+    fn build(
+        &self,
+        app: &mut App,
+    ) {
+        app.add_message::<Sfx>()
+            .init_resource::<Settings>()
+            .init_resource::<Sounds>()
+            .insert_resource(Focused(true))
+            .add_systems(Startup, spawn_music)
+            .add_systems(
+                Update,
+                (
+                    mute_keyboard,
+                    focus,
+                    update_music_volume.run_if(resource_changed::<Settings>),
+                    play_sfx,
+                    despawn_stalled_sfx,
+                )
+                    .chain(),
+            )
+            .add_setting(Setting {
+                label: "settings.master_volume",
+                value: |world| percent(world.resource::<Settings>().master),
+                adjust: |world, delta| {
+                    let mut settings = world.resource_mut::<Settings>();
+                    settings.master = step(settings.master, delta);
+                },
+            })
+            .add_setting(Setting {
+                label: "settings.music_volume",
+                value: |world| percent(world.resource::<Settings>().music),
+                adjust: |world, delta| {
+                    let mut settings = world.resource_mut::<Settings>();
+                    settings.music = step(settings.music, delta);
+                },
+            })
+            .add_setting(Setting {
+                label: "settings.sfx_volume",
+                value: |world| percent(world.resource::<Settings>().sfx),
+                adjust: |world, delta| {
+                    let mut settings = world.resource_mut::<Settings>();
+                    settings.sfx = step(settings.sfx, delta);
+                },
+            })
+            .add_setting(Setting {
+                label: "settings.mute",
+                value: |world| {
+                    if world.resource::<Settings>().muted {
+                        locale::translate(world, "settings.on")
+                    } else {
+                        locale::translate(world, "settings.off")
+                    }
+                },
+                adjust: |world, _| {
+                    let mut settings = world.resource_mut::<Settings>();
+                    settings.muted = !settings.muted;
+                },
+            });
+    }
+}
+
+/// Write to play a sound effect.
+#[derive(Message, Clone, Copy, Debug)]
+pub enum Sfx {
+    Click,
+    Match,
+    Mismatch,
+    Undo,
+    Shuffle,
+    Victory,
+    Defeat,
+}
+
+/// Volumes are linear, in `0.0..=1.0`. Music and effects are scaled by [Settings::master].
+#[derive(Resource, Debug)]
+pub struct Settings {
+    pub master: f32,
+    pub music: f32,
+    pub sfx: f32,
+    pub muted: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            master: 0.8,
+            music: 0.5,
+            sfx: 1.0,
+            muted: false,
+        }
+    }
+}
+
+impl Settings {
+    fn volume(
+        &self,
+        channel: f32,
+    ) -> Volume {
+        if self.muted {
+            Volume::SILENT
+        } else {
+            Volume::Linear(self.master * channel)
+        }
+    }
+
+    pub fn music_volume(&self) -> Volume {
+        self.volume(self.music)
+    }
+
+    pub fn sfx_volume(&self) -> Volume {
+        self.volume(self.sfx)
+    }
+}
+
+#[derive(Resource)]
+struct Sounds {
+    music: Handle<AudioSource>,
+    click: Handle<AudioSource>,
+    r#match: Handle<AudioSource>,
+    mismatch: Handle<AudioSource>,
+    undo: Handle<AudioSource>,
+    shuffle: Handle<AudioSource>,
+    victory: Handle<AudioSource>,
+    defeat: Handle<AudioSource>,
+}
+
+impl FromWorld for Sounds {
+    fn from_world(world: &mut World) -> Self {
+        let asset_server = world.resource::<AssetServer>();
+
+        Self {
+            music: asset_server.load(asset::MUSIC),
+            click: asset_server.load(asset::CLICK),
+            r#match: asset_server.load(asset::MATCH),
+            mismatch: asset_server.load(asset::MISMATCH),
+            undo: asset_server.load(asset::UNDO),
+            shuffle: asset_server.load(asset::SHUFFLE),
+            victory: asset_server.load(asset::VICTORY),
+            defeat: asset_server.load(asset::DEFEAT),
+        }
+    }
+}
+
+impl Sounds {
+    fn sfx(
+        &self,
+        sfx: Sfx,
+    ) -> Handle<AudioSource> {
+        match sfx {
+            Sfx::Click => self.click.clone(),
+            Sfx::Match => self.r#match.clone(),
+            Sfx::Mismatch => self.mismatch.clone(),
+            Sfx::Undo => self.undo.clone(),
+            Sfx::Shuffle => self.shuffle.clone(),
+            Sfx::Victory => self.victory.clone(),
+            Sfx::Defeat => self.defeat.clone(),
+        }
+    }
+}
+
+#[derive(Component)]
+struct Music;
+
+#[derive(Component)]
+struct SfxTimeout(Timer);
+
+/// Whether any window has focus.
+#[derive(Resource)]
+struct Focused(bool);
+
+fn percent(value: f32) -> String {
+    format!("{:.0}%", value * 100.0)
+}
+
+fn step(
+    value: f32,
+    delta: i32,
+) -> f32 {
+    (value + delta as f32 * VOLUME_STEP).clamp(0.0, 1.0)
+}
+
+fn spawn_music(
+    mut commands: Commands,
+    sounds: Res<Sounds>,
+    settings: Res<Settings>,
+) {
+    commands.spawn((
+        Music,
+        AudioPlayer::new(sounds.music.clone()),
+        PlaybackSettings::LOOP.with_volume(settings.music_volume()),
+    ));
+}
+
+fn mute_keyboard(
+    key: Res<ButtonInput<KeyCode>>,
+    mut settings: ResMut<Settings>,
+) {
+    if key.just_pressed(KeyCode::KeyM) {
+        settings.muted = !settings.muted;
+    }
+}
+
+/// Audio is played on its own thread, so it keeps going even though the app barely updates while
+/// unfocused (see `unfocused_mode` of [super::default::DefaultWinitSettings]). Pause everything
+/// until focus returns, which is a window event and thus still wakes the app up.
+fn focus(
+    mut focus_events: MessageReader<WindowFocused>,
+    mut focused: ResMut<Focused>,
+    sinks: Query<&AudioSink>,
+) {
+    let Some(event) = focus_events.read().last() else {
+        return;
+    };
+
+    if focused.0 == event.focused {
+        return;
+    }
+
+    focused.0 = event.focused;
+
+    for sink in sinks {
+        if focused.0 {
+            sink.play();
+        } else {
+            sink.pause();
+        }
+    }
+}
+
+fn update_music_volume(
+    settings: Res<Settings>,
+    mut music: Query<&mut AudioSink, With<Music>>,
+) {
+    for mut sink in music.iter_mut() {
+        sink.set_volume(settings.music_volume());
+    }
+}
+
+fn play_sfx(
+    mut commands: Commands,
+    mut sfx: MessageReader<Sfx>,
+    sounds: Res<Sounds>,
+    settings: Res<Settings>,
+    focused: Res<Focused>,
+) {
+    for sfx in sfx.read() {
+        if !focused.0 || settings.muted {
+            continue;
+        }
+
+        commands.spawn((
+            SfxTimeout(Timer::new(SFX_TIMEOUT, TimerMode::Once)),
+            AudioPlayer::new(sounds.sfx(*sfx)),
+            PlaybackSettings::DESPAWN.with_volume(settings.sfx_volume()),
+        ));
+    }
+}
+
+/// Without an audio device (or if the sound failed to load) no [AudioSink] is ever added and
+/// [PlaybackSettings::DESPAWN] never kicks in.
+fn despawn_stalled_sfx(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut sfx: Query<(Entity, &mut SfxTimeout, Has<AudioSink>)>,
+) {
+    for (entity, mut timeout, playing) in sfx.iter_mut() {
+        if playing {
+            commands.entity(entity).remove::<SfxTimeout>();
+        } else if timeout.0.tick(time.delta()).is_finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// System writing `sfx`, e.g. for `OnEnter` schedules.
+pub fn play(sfx: Sfx) -> impl FnMut(MessageWriter<Sfx>) {
+    move |mut writer| {
+        writer.write(sfx);
+    }
+}
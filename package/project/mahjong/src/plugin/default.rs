@@ -1,9 +1,9 @@
 use std::time::Duration;
 
+use crate::plugin::{process, variant::Variant};
 use bevy::{
-    asset::AssetMetaCheck,
+    asset::{AssetMetaCheck, AssetMode, io::AssetSourceId},
     camera::{ScalingMode, visibility::RenderLayers},
-    diagnostic::FrameTimeDiagnosticsPlugin,
     log::LogPlugin,
     prelude::*,
     window::PresentMode,
@@ -19,7 +19,7 @@ impl bevy::prelude::Plugin for Plugin {
     ) {
         let default_winit_settings = DefaultWinitSettings(WinitSettings {
             focused_mode: UpdateMode::Reactive {
-                wait: Duration::from_millis((1000.0 / 1.0) as u64),
+                wait: REACTIVE_WAIT,
                 react_to_device_events: true,
                 react_to_user_events: true,
                 react_to_window_events: true,
@@ -31,9 +31,32 @@ impl bevy::prelude::Plugin for Plugin {
                 react_to_window_events: true,
             },
         });
-        app.insert_resource(default_winit_settings.clone())
+        // Before `WinitPlugin`, which would otherwise update continuously.
+        app.insert_resource(default_winit_settings.0.clone())
             .insert_resource(default_winit_settings);
 
+        let processed = cfg!(any(feature = "process", target_arch = "wasm32")) || has_processed();
+        let asset_plugin = AssetPlugin {
+            // Meta files of the source art are for the asset processor, see [process].
+            meta_check: if processed {
+                AssetMetaCheck::Always
+            } else {
+                AssetMetaCheck::Never
+            },
+            mode: if processed {
+                AssetMode::Processed
+            } else {
+                AssetMode::Unprocessed
+            },
+            file_path: "asset".to_string(),
+            processed_file_path: process::PROCESSED_DIR.to_string(),
+            ..default()
+        };
+        app.register_asset_source(
+            AssetSourceId::Default,
+            Variant::select().source(&asset_plugin),
+        );
+
         app.add_plugins((
             bevy::DefaultPlugins
                 .set(LogPlugin {
@@ -51,17 +74,8 @@ impl bevy::prelude::Plugin for Plugin {
                     }),
                     ..default()
                 })
-                .set(AssetPlugin {
-                    meta_check: if cfg!(target_family = "wasm") {
-                        AssetMetaCheck::Never
-                    } else {
-                        AssetMetaCheck::Always
-                    },
-                    file_path: "asset".to_string(),
-                    ..default()
-                }),
+                .set(asset_plugin),
             MeshPickingPlugin,
-            FrameTimeDiagnosticsPlugin::default(),
         ));
 
         let base_cam = (
@@ -75,8 +89,8 @@ impl bevy::prelude::Plugin for Plugin {
             Msaa::Off,
         );
 
-        for layer in 0..=1 as usize {
-            app.world_mut().spawn((
+        for layer in 0..=OVERLAY_LAYER {
+            let mut camera = app.world_mut().spawn((
                 base_cam.clone(),
                 Camera {
                     clear_color: ClearColorConfig::None,
@@ -85,9 +99,39 @@ impl bevy::prelude::Plugin for Plugin {
                 },
                 RenderLayers::layer(layer),
             ));
+
+            if layer == OVERLAY_LAYER {
+                camera.insert(OverlayCamera);
+            }
         }
     }
 }
 
+/// Whether the source art was processed before, e.g. by `mahjong-assets --process`.
+#[cfg(not(target_arch = "wasm32"))]
+fn has_processed() -> bool {
+    bevy::asset::io::file::FileAssetReader::get_base_path()
+        .join(process::PROCESSED_DIR)
+        .is_dir()
+}
+
+#[cfg(target_arch = "wasm32")]
+fn has_processed() -> bool {
+    true
+}
+
+/// Longest time between updates while focused, unless something asks for more (see
+/// [super::frame_budget]). Enough for the clock of timed modes and anything that is polled, such as
+/// the connection to a relay.
+const REACTIVE_WAIT: Duration = Duration::from_millis(250);
+
+/// Render layer drawn on top of the world, used for the HUD and other overlays.
+pub const OVERLAY_LAYER: usize = 1;
+
+/// Marks the camera rendering [OVERLAY_LAYER]. `bevy_ui` nodes target it through [UiTargetCamera].
+#[derive(Component)]
+pub struct OverlayCamera;
+
+/// [WinitSettings] while nothing asks for continuous updates, see [super::frame_budget].
 #[derive(Resource, Clone)]
-pub struct DefaultWinitSettings(pub WinitSettings);
\ No newline at end of file
+pub struct DefaultWinitSettings(pub WinitSettings);
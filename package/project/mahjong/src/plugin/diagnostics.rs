@@ -0,0 +1,236 @@
+//! On-screen diagnostics for development, toggled with F: frame rate and a graph of frame times,
+//! what the game is made of at the moment, and how it got there. Only compiled in with the
+//! `diagnostics` feature, which `debug` enables.
+
+use crate::plugin::{
+    default::OverlayCamera,
+    frame_budget::FrameBudget,
+    scene::{
+        self,
+        in_game::{self, InGame, Seed},
+        main_menu::MainMenu,
+    },
+};
+use bevy::{
+    diagnostic::{
+        Diagnostic, DiagnosticPath, DiagnosticsStore, EntityCountDiagnosticsPlugin,
+        FrameTimeDiagnosticsPlugin, RegisterDiagnostic,
+    },
+    ecs::system::SystemParam,
+    prelude::*,
+};
+
+pub struct Plugin;
+
+impl bevy::prelude::Plugin for Plugin {
+    fn build(
+        &self,
+        app: &mut App,
+    ) {
+        app.add_plugins((
+            FrameTimeDiagnosticsPlugin::new(FRAMES),
+            EntityCountDiagnosticsPlugin::default(),
+        ))
+        .register_diagnostic(Diagnostic::new(in_game::TILES))
+        .register_diagnostic(Diagnostic::new(in_game::GENERATION).with_suffix(" ms"))
+        .add_systems(bevy::app::Startup, spawn)
+        .add_systems(Update, (toggle, update).chain());
+    }
+}
+
+/// Frames shown by the graph.
+const FRAMES: usize = 120;
+/// Frame time at the top of the graph (ms).
+const GRAPH_MAX: f64 = 50.0;
+/// Height of the graph (logical pixels).
+const GRAPH_HEIGHT: f32 = 48.0;
+
+#[derive(Component)]
+struct Overlay;
+
+/// Text listing every diagnostic.
+#[derive(Component)]
+struct Readout;
+
+/// Bar of the graph showing the frame time of the nth of the last [FRAMES] frames.
+#[derive(Component)]
+struct Bar(usize);
+
+fn spawn(
+    mut commands: Commands,
+    camera: Single<Entity, With<OverlayCamera>>,
+) {
+    let bars = (0..FRAMES).map(|index| {
+        (
+            Bar(index),
+            Node {
+                width: Val::Px(2.0),
+                ..default()
+            },
+            BackgroundColor(Color::NONE),
+            Pickable::IGNORE,
+        )
+    });
+
+    commands.spawn((
+        Overlay,
+        UiTargetCamera(*camera),
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(8.0),
+            top: Val::Px(8.0),
+            padding: UiRect::all(Val::Px(6.0)),
+            row_gap: Val::Px(6.0),
+            flex_direction: FlexDirection::Column,
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.75)),
+        GlobalZIndex(i32::MAX),
+        Visibility::Hidden,
+        Pickable::IGNORE,
+        children![
+            (
+                Readout,
+                Text::default(),
+                TextFont {
+                    font_size: 14.0,
+                    ..default()
+                },
+                Pickable::IGNORE,
+            ),
+            (
+                Node {
+                    height: Val::Px(GRAPH_HEIGHT),
+                    align_items: AlignItems::End,
+                    column_gap: Val::Px(1.0),
+                    ..default()
+                },
+                Pickable::IGNORE,
+                Children::spawn(SpawnIter(bars)),
+            ),
+        ],
+    ));
+}
+
+fn toggle(
+    key: Res<ButtonInput<KeyCode>>,
+    mut overlay: Single<&mut Visibility, With<Overlay>>,
+) {
+    if key.just_pressed(KeyCode::KeyF) {
+        overlay.toggle_visible_hidden();
+    }
+}
+
+/// Latest value of the diagnostic at `path`, smoothed if `smoothed`.
+fn format(
+    diagnostics: &DiagnosticsStore,
+    path: &DiagnosticPath,
+    smoothed: bool,
+) -> String {
+    let Some(diagnostic) = diagnostics.get(path) else {
+        return "-".to_string();
+    };
+
+    let value = if smoothed {
+        diagnostic.smoothed()
+    } else {
+        diagnostic.value()
+    };
+
+    value.map_or("-".to_string(), |value| {
+        format!("{value:.1}{}", diagnostic.suffix)
+    })
+}
+
+/// States the app is in, from the outermost.
+#[derive(SystemParam)]
+struct StateStack<'w> {
+    startup: Res<'w, State<scene::Startup>>,
+    main_menu: Option<Res<'w, State<MainMenu>>>,
+    in_game: Option<Res<'w, State<InGame>>>,
+}
+
+impl StateStack<'_> {
+    /// E.g. `Startup::MainMenu > MainMenu::Root`.
+    fn format(&self) -> String {
+        let mut states = vec![format!("Startup::{:?}", self.startup.get())];
+        states.extend(
+            self.main_menu
+                .as_ref()
+                .map(|state| format!("MainMenu::{:?}", state.get())),
+        );
+        states.extend(
+            self.in_game
+                .as_ref()
+                .map(|state| format!("InGame::{:?}", state.get())),
+        );
+
+        states.join(" > ")
+    }
+}
+
+fn update(
+    overlay: Single<&Visibility, With<Overlay>>,
+    mut readout: Single<&mut Text, With<Readout>>,
+    mut bars: Query<(&Bar, &mut Node, &mut BackgroundColor)>,
+    diagnostics: Res<DiagnosticsStore>,
+    states: StateStack,
+    seed: Res<Seed>,
+    mut frame_budget: ResMut<FrameBudget>,
+) {
+    if **overlay == Visibility::Hidden {
+        return;
+    }
+
+    // Frame times are meaningless while updates wait for input.
+    frame_budget.request("diagnostics");
+
+    let lines = [
+        format!(
+            "FPS: {} ({})",
+            format(&diagnostics, &FrameTimeDiagnosticsPlugin::FPS, true),
+            format(&diagnostics, &FrameTimeDiagnosticsPlugin::FRAME_TIME, true),
+        ),
+        format!(
+            "Entities: {}",
+            format(
+                &diagnostics,
+                &EntityCountDiagnosticsPlugin::ENTITY_COUNT,
+                false
+            )
+        ),
+        format!("Tiles: {}", format(&diagnostics, &in_game::TILES, false)),
+        format!(
+            "Generation: {}",
+            format(&diagnostics, &in_game::GENERATION, false)
+        ),
+        format!(
+            "Seed: {}",
+            seed.map_or("-".to_string(), |seed| seed.to_string())
+        ),
+        format!("State: {}", states.format()),
+    ];
+    readout.0 = lines.join("\n");
+
+    let frame_times: Vec<f64> = diagnostics
+        .get(&FrameTimeDiagnosticsPlugin::FRAME_TIME)
+        .map(|diagnostic| diagnostic.values().copied().collect())
+        .unwrap_or_default();
+    // The latest frame is the rightmost bar.
+    let offset = FRAMES.saturating_sub(frame_times.len());
+
+    for (bar, mut node, mut color) in &mut bars {
+        let Some(frame_time) = bar.0.checked_sub(offset).map(|index| frame_times[index]) else {
+            continue;
+        };
+
+        node.height = Val::Px((frame_time / GRAPH_MAX).min(1.0) as f32 * GRAPH_HEIGHT);
+        color.0 = if frame_time <= 1000.0 / 60.0 {
+            Color::srgb(0.2, 0.8, 0.2)
+        } else if frame_time <= 1000.0 / 30.0 {
+            Color::srgb(0.9, 0.8, 0.1)
+        } else {
+            Color::srgb(0.9, 0.2, 0.2)
+        };
+    }
+}
@@ -1,13 +1,20 @@
 use bevy::prelude::*;
-// use bevy_inspector_egui::{bevy_egui::EguiPlugin, quick::WorldInspectorPlugin};
 
+/// Plugins of other crates which are only for development, each behind a feature.
 pub struct Plugin;
 
 impl bevy::prelude::Plugin for Plugin {
+    #[cfg_attr(not(feature = "inspector"), allow(unused_variables))]
     fn build(
         &self,
         app: &mut App,
     ) {
-        // app.add_plugins((EguiPlugin::default(), WorldInspectorPlugin::new()));
+        #[cfg(feature = "inspector")]
+        {
+            use bevy_inspector_egui::{bevy_egui::EguiPlugin, quick::WorldInspectorPlugin};
+
+            // Panels of the game in progress are added by the game, see `in_game::inspector`.
+            app.add_plugins((EguiPlugin::default(), WorldInspectorPlugin::new()));
+        }
     }
 }
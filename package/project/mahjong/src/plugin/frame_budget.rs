@@ -0,0 +1,67 @@
+//! The app only updates in reaction to input by default (see [DefaultWinitSettings]), which spares
+//! laptop batteries and browser tabs. Whatever has to move on its own, e.g. the deal or a timer
+//! running out, asks for continuous updates through [FrameBudget], every frame for as long as it
+//! does. Nothing has to be undone once it is done, so leaving a state early can not keep the app
+//! updating.
+
+use crate::plugin::default::DefaultWinitSettings;
+use bevy::{
+    platform::collections::HashSet,
+    prelude::*,
+    winit::{UpdateMode, WinitSettings},
+};
+
+pub struct Plugin;
+
+impl bevy::prelude::Plugin for Plugin {
+    fn build(
+        &self,
+        app: &mut App,
+    ) {
+        app.init_resource::<FrameBudget>().add_systems(Last, apply);
+    }
+}
+
+/// Reasons to update continuously this frame, e.g. `"deal"`. Cleared at the end of every frame.
+#[derive(Resource, Default)]
+pub struct FrameBudget {
+    requests: HashSet<&'static str>,
+    /// Whether [WinitSettings] are continuous.
+    continuous: bool,
+}
+
+impl FrameBudget {
+    /// Keeps the app updating continuously through the next frame while focused. `reason` is
+    /// logged once updates become continuous.
+    pub fn request(
+        &mut self,
+        reason: &'static str,
+    ) {
+        self.requests.insert(reason);
+    }
+}
+
+/// Switches [WinitSettings] between continuous and [DefaultWinitSettings], only when needed since
+/// either wakes the event loop.
+fn apply(
+    mut budget: ResMut<FrameBudget>,
+    default_winit_settings: Res<DefaultWinitSettings>,
+    mut winit_settings: ResMut<WinitSettings>,
+) {
+    let continuous = !budget.requests.is_empty();
+
+    if continuous != budget.continuous {
+        if continuous {
+            debug!("Updating continuously for {:?}", budget.requests);
+            // Unfocused, e.g. in a background tab, nothing is worth the power.
+            winit_settings.focused_mode = UpdateMode::Continuous;
+        } else {
+            debug!("Updating reactively");
+            *winit_settings = default_winit_settings.0.clone();
+        }
+
+        budget.continuous = continuous;
+    }
+
+    budget.requests.clear();
+}
@@ -1,11 +1,11 @@
-use bevy::{prelude::*, camera::ScalingMode};
+use bevy::prelude::*;
 
 pub struct Plugin;
 
 impl bevy::prelude::Plugin for Plugin {
     fn build(
         &self,
-        app: &mut App,
+        _app: &mut App,
     ) {
         // app.insert_resource(WindowScaling::default())
         //     .add_systems(PreUpdate, on_preupdate);
@@ -0,0 +1,226 @@
+use crate::plugin::{
+    platform::{Platform, PlatformTrait},
+    scene::main_menu::settings::{AppExt, Setting},
+};
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader},
+    ecs::system::SystemParam,
+    prelude::*,
+    window::PrimaryWindow,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+use thiserror::Error;
+
+pub mod asset {
+    /// Language (ISO 639-1) and catalog of every bundled translation. The first one is the
+    /// fallback for missing messages.
+    pub const CATALOGS: [(&str, &str); 2] = [
+        ("en", "locale/en.locale.ron"),
+        ("sv", "locale/sv.locale.ron"),
+    ];
+}
+
+/// Replaces Bevy's default font, which only covers ASCII.
+// Embedded instead of loaded, so that every text can use it from the first frame without a handle.
+// It lives outside `asset/`, which Git LFS stores as pointers that would compile in as the font.
+const FONT: &[u8] = include_bytes!("../../font/DejaVuSansMono.ttf");
+
+pub struct Plugin;
+
+impl bevy::prelude::Plugin for Plugin {
+    fn build(
+        &self,
+        app: &mut App,
+    ) {
+        app.world_mut()
+            .resource_mut::<Assets<Font>>()
+            .insert(
+                AssetId::default(),
+                Font::try_from_bytes(FONT.to_vec()).expect("Invalid font!"),
+            )
+            .unwrap();
+
+        app.init_asset::<Catalog>()
+            .init_asset_loader::<CatalogLoader>()
+            .init_resource::<Locale>()
+            .add_systems(Update, (localize_texts, localize_window))
+            .add_setting(Setting {
+                label: "settings.language",
+                value: |world| {
+                    let locale = world.resource::<Locale>();
+                    world
+                        .resource::<Assets<Catalog>>()
+                        .get(locale.active())
+                        .map(|catalog| catalog.name.clone())
+                        .unwrap_or_default()
+                },
+                adjust: |world, delta| world.resource_mut::<Locale>().step(delta),
+            });
+    }
+}
+
+/// Translated messages of one language, keyed by message id. Loaded from `*.locale.ron` files.
+#[derive(Asset, TypePath, Deserialize, Debug)]
+pub struct Catalog {
+    /// Name of the language, in the language itself.
+    pub name: String,
+    pub messages: HashMap<String, String>,
+}
+
+#[derive(Default, TypePath)]
+pub struct CatalogLoader;
+
+#[derive(Debug, Error)]
+pub enum CatalogLoaderError {
+    #[error("Could not read catalog: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not parse catalog: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+}
+
+impl AssetLoader for CatalogLoader {
+    type Asset = Catalog;
+    type Settings = ();
+    type Error = CatalogLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["locale.ron"]
+    }
+}
+
+/// All bundled catalogs along with the one currently in use. Defaults to the first of
+/// [PlatformTrait::languages] which is bundled.
+#[derive(Resource)]
+pub struct Locale {
+    handles: Vec<Handle<Catalog>>,
+    active: usize,
+}
+
+impl FromWorld for Locale {
+    fn from_world(world: &mut World) -> Self {
+        let preferred = world.resource::<Platform>().languages();
+        let asset_server = world.resource::<AssetServer>();
+
+        let active = preferred
+            .iter()
+            .find_map(|tag| {
+                let language = tag.split('-').next()?.to_lowercase();
+                asset::CATALOGS
+                    .iter()
+                    .position(|(other, _)| *other == language)
+            })
+            .unwrap_or(0);
+
+        info!(
+            "Using language \"{}\" (preferred: {preferred:?})",
+            asset::CATALOGS[active].0
+        );
+
+        Self {
+            handles: asset::CATALOGS
+                .iter()
+                .map(|(_, path)| asset_server.load(*path))
+                .collect(),
+            active,
+        }
+    }
+}
+
+impl Locale {
+    pub fn active(&self) -> &Handle<Catalog> {
+        &self.handles[self.active]
+    }
+
+    pub fn step(
+        &mut self,
+        delta: i32,
+    ) {
+        self.active = (self.active as i32 + delta).rem_euclid(self.handles.len() as i32) as usize;
+    }
+
+    /// `key` in the active catalog, falling back to the first catalog and then the key itself.
+    pub fn translate(
+        &self,
+        catalogs: &Assets<Catalog>,
+        key: &str,
+    ) -> String {
+        [self.active(), &self.handles[0]]
+            .into_iter()
+            .filter_map(|handle| catalogs.get(handle))
+            .find_map(|catalog| catalog.messages.get(key))
+            .cloned()
+            .unwrap_or_else(|| key.to_string())
+    }
+}
+
+/// [Locale::translate] for systems.
+#[derive(SystemParam)]
+pub struct Tr<'w, 's> {
+    locale: Res<'w, Locale>,
+    catalogs: Res<'w, Assets<Catalog>>,
+    catalog_events: MessageReader<'w, 's, AssetEvent<Catalog>>,
+}
+
+impl Tr<'_, '_> {
+    pub fn get(
+        &self,
+        key: &str,
+    ) -> String {
+        self.locale.translate(&self.catalogs, key)
+    }
+
+    /// Whether translations may differ from the last time this was called, i.e. the language was
+    /// switched or a catalog was (re)loaded.
+    pub fn changed(&mut self) -> bool {
+        let loaded = self.catalog_events.read().count() != 0;
+        self.locale.is_changed() || loaded
+    }
+}
+
+/// [Locale::translate] for e.g. [Setting::value].
+pub fn translate(
+    world: &World,
+    key: &str,
+) -> String {
+    world
+        .resource::<Locale>()
+        .translate(world.resource::<Assets<Catalog>>(), key)
+}
+
+/// Keeps the [Text] of the entity translated from the message `key`.
+#[derive(Component, Clone, Debug)]
+pub struct Localized(pub &'static str);
+
+fn localize_texts(
+    mut tr: Tr,
+    mut texts: Query<(Ref<Localized>, &mut Text)>,
+) {
+    let changed = tr.changed();
+
+    for (localized, mut text) in &mut texts {
+        if changed || localized.is_changed() {
+            text.0 = tr.get(localized.0);
+        }
+    }
+}
+
+fn localize_window(
+    mut tr: Tr,
+    mut window: Single<&mut Window, With<PrimaryWindow>>,
+) {
+    if tr.changed() {
+        window.title = tr.get("window.title");
+    }
+}
@@ -1,6 +1,14 @@
+pub mod audio;
 pub mod default;
+#[cfg(feature = "diagnostics")]
+pub mod diagnostics;
 pub mod external;
+pub mod frame_budget;
+pub mod locale;
+pub mod platform;
+pub mod process;
 pub mod scene;
+pub mod variant;
 pub mod global;
 
 use bevy::prelude::*;
@@ -14,9 +22,17 @@ impl bevy::prelude::Plugin for Plugin {
     ) {
         app.add_plugins((
             default::Plugin,
+            frame_budget::Plugin,
+            process::Plugin,
+            platform::PlatformPlugin,
+            locale::Plugin,
+            audio::Plugin,
             external::Plugin,
             global::Plugin,
             scene::Plugin,
         ));
+
+        #[cfg(feature = "diagnostics")]
+        app.add_plugins(diagnostics::Plugin);
     }
 }
\ No newline at end of file
@@ -0,0 +1,319 @@
+use bevy::prelude::*;
+pub use implementation::Platform;
+
+pub struct PlatformPlugin;
+
+impl bevy::prelude::Plugin for PlatformPlugin {
+    fn build(
+        &self,
+        app: &mut App,
+    ) {
+        app.add_message::<SeedChanged>()
+            .add_plugins(implementation::PlatformPlugin);
+    }
+}
+
+pub trait Observer<T> {
+    fn observe(
+        &mut self,
+        object: T,
+    );
+}
+
+#[derive(Message)]
+pub struct SeedChanged {
+    pub new: u64,
+}
+
+pub trait PlatformTrait: Resource + Default {
+    const DEFAULT_MSG: &'static str = "Not implemented for this platform!";
+
+    type ObserverItem;
+
+    fn rng_seed_observe(
+        &mut self,
+        _observer: &mut dyn Observer<Self::ObserverItem>,
+    ) {
+        debug!("rng_seed_observe {}", Self::DEFAULT_MSG);
+    }
+
+    fn rng_seed_get(&self) -> Option<u64> {
+        debug!("rng_seed_get {}", Self::DEFAULT_MSG);
+        None
+    }
+
+    fn rng_seed_set(
+        &self,
+        _seed: u64,
+    ) {
+        debug!("rng_seed_set {}", Self::DEFAULT_MSG);
+    }
+
+    /// Preferred languages of the user as BCP 47 tags (e.g. "sv-SE"), most preferred first.
+    fn languages(&self) -> Vec<String> {
+        debug!("languages {}", Self::DEFAULT_MSG);
+        Vec::new()
+    }
+
+    /// WebSocket URL of the relay for online races, if given by the user.
+    fn relay(&self) -> Option<String> {
+        debug!("relay {}", Self::DEFAULT_MSG);
+        None
+    }
+
+    /// Key of the [crate::plugin::variant::Variant] of images to load, if given by the user.
+    fn asset_variant(&self) -> Option<String> {
+        debug!("asset_variant {}", Self::DEFAULT_MSG);
+        None
+    }
+}
+
+/// NATIVE
+#[cfg(not(target_arch = "wasm32"))]
+mod implementation {
+    use super::*;
+    use std::sync::Mutex;
+
+    pub struct PlatformPlugin;
+
+    impl bevy::prelude::Plugin for PlatformPlugin {
+        fn build(
+            &self,
+            app: &mut App,
+        ) {
+            let platform = Platform::default();
+            app.insert_resource(platform);
+        }
+    }
+
+    /// Remembers the seed of the current game, so that it is dealt again by e.g. a restart. Players
+    /// race on the same deal by starting with the same `--seed <n>` argument.
+    #[derive(Resource)]
+    pub struct Platform {
+        seed: Mutex<Option<u64>>,
+    }
+
+    impl Default for Platform {
+        fn default() -> Self {
+            let seed = std::env::args()
+                .skip_while(|arg| arg != "--seed")
+                .nth(1)
+                .and_then(|seed| seed.parse().ok());
+
+            Self {
+                seed: Mutex::new(seed),
+            }
+        }
+    }
+
+    impl PlatformTrait for Platform {
+        type ObserverItem = ();
+
+        fn rng_seed_get(&self) -> Option<u64> {
+            *self.seed.lock().unwrap()
+        }
+
+        fn rng_seed_set(
+            &self,
+            seed: u64,
+        ) {
+            *self.seed.lock().unwrap() = Some(seed);
+        }
+
+        fn languages(&self) -> Vec<String> {
+            // POSIX locales look like "sv_SE.UTF-8".
+            ["LC_ALL", "LC_MESSAGES", "LANG"]
+                .iter()
+                .filter_map(|variable| std::env::var(variable).ok())
+                .filter_map(|locale| {
+                    let language = locale.split(['.', '@']).next()?.replace('_', "-");
+                    (!language.is_empty() && language != "C" && language != "POSIX")
+                        .then_some(language)
+                })
+                .collect()
+        }
+
+        fn relay(&self) -> Option<String> {
+            std::env::var("MAHJONG_RELAY").ok()
+        }
+
+        fn asset_variant(&self) -> Option<String> {
+            std::env::var("MAHJONG_ASSET_VARIANT").ok()
+        }
+    }
+
+    impl Platform {}
+}
+
+/// WASM
+#[cfg(target_arch = "wasm32")]
+mod implementation {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen::prelude::*;
+    use web_sys::HashChangeEvent;
+
+    pub struct PlatformPlugin;
+
+    impl bevy::prelude::Plugin for PlatformPlugin {
+        fn build(
+            &self,
+            app: &mut App,
+        ) {
+            let mut platform = Platform::default();
+            let mut observer = HashObserver::default();
+            platform.rng_seed_observe(&mut observer);
+
+            app.insert_resource(platform)
+                .insert_non_send_resource(observer)
+                .add_systems(PreUpdate, poll_hash_changes);
+        }
+    }
+
+    #[derive(Resource, Default)]
+    pub struct Platform;
+
+    // Resource: NonSend
+    #[derive(Default)]
+    pub struct HashObserver {
+        pub closure: Option<Closure<dyn FnMut(HashChangeEvent)>>,
+        pub pending: Option<Rc<RefCell<Option<(String, String)>>>>,
+    }
+
+    impl
+        Observer<(
+            Closure<dyn FnMut(HashChangeEvent)>,
+            Rc<RefCell<Option<(String, String)>>>,
+        )> for HashObserver
+    {
+        fn observe(
+            &mut self,
+            object: (
+                Closure<dyn FnMut(HashChangeEvent)>,
+                Rc<RefCell<Option<(String, String)>>>,
+            ),
+        ) {
+            self.closure = Some(object.0);
+            self.pending = Some(object.1);
+        }
+    }
+
+    impl Drop for HashObserver {
+        fn drop(&mut self) {
+            if self.closure.is_some() {
+                let window = web_sys::window().unwrap();
+                window
+                    .remove_event_listener_with_callback(
+                        "hashchange",
+                        self.closure.take().unwrap().as_ref().unchecked_ref(),
+                    )
+                    .unwrap();
+            }
+        }
+    }
+
+    impl PlatformTrait for Platform {
+        type ObserverItem = (
+            Closure<dyn FnMut(HashChangeEvent)>,
+            Rc<RefCell<Option<(String, String)>>>,
+        );
+
+        fn rng_seed_observe(
+            &mut self,
+            observer: &mut dyn Observer<Self::ObserverItem>,
+        ) {
+            let window = web_sys::window().expect("no window found");
+            let pending = Rc::new(RefCell::new(None));
+
+            let closure = Closure::wrap(Box::new({
+                let pending = Rc::clone(&pending);
+                move |event: HashChangeEvent| {
+                    let url_old = event.old_url();
+                    let url_new = event.new_url();
+                    if let Some(hash_old) = url_old.split_once('#') {
+                        let hash_old = hash_old.1;
+                        let hash_new = url_new.split_once('#').unwrap().1;
+                        *pending.borrow_mut() = Some((hash_old.to_owned(), hash_new.to_owned()));
+                    }
+                }
+            }) as Box<dyn FnMut(_)>);
+
+            window
+                .add_event_listener_with_callback("hashchange", closure.as_ref().unchecked_ref())
+                .unwrap();
+
+            observer.observe((closure, pending));
+        }
+
+        fn rng_seed_get(&self) -> Option<u64> {
+            Self::get_fragment()
+        }
+
+        fn rng_seed_set(
+            &self,
+            seed: u64,
+        ) {
+            Self::set_fragment(&seed.to_string())
+        }
+
+        fn languages(&self) -> Vec<String> {
+            web_sys::window()
+                .and_then(|window| window.navigator().language())
+                .into_iter()
+                .collect()
+        }
+
+        fn relay(&self) -> Option<String> {
+            Self::get_query("relay")
+        }
+
+        fn asset_variant(&self) -> Option<String> {
+            Self::get_query("assets")
+        }
+    }
+
+    impl Platform {
+        /// Value of `key` in the query of the page, e.g. `?relay=ws://...`.
+        fn get_query(key: &str) -> Option<String> {
+            let search = web_sys::window()?.location().search().ok()?;
+            search
+                .trim_start_matches('?')
+                .split('&')
+                .find_map(|pair| pair.strip_prefix(key)?.strip_prefix('='))
+                .map(str::to_string)
+        }
+
+        fn get_fragment() -> Option<u64> {
+            let window = web_sys::window().expect("no global `window` exists");
+            let location = window.location();
+            location.hash().ok()?.trim_start_matches('#').parse().ok()
+        }
+
+        fn set_fragment(fragment_hash: &str) {
+            let window = web_sys::window().expect("no global `window` exists");
+            let location = window.location();
+
+            location
+                .set_hash(format!("#{fragment_hash}").as_str())
+                .expect("failed to set hash");
+        }
+    }
+
+    fn poll_hash_changes(
+        observer: NonSend<HashObserver>,
+        mut writer: MessageWriter<SeedChanged>,
+    ) {
+        if let Some(pending) = observer.pending.as_ref() {
+            let new = pending.borrow_mut().take();
+            if let Some((old, new)) = new {
+                if old != new {
+                    debug!("New hash! ({new})");
+                    let new = new.parse().unwrap();
+                    writer.write(SeedChanged { new });
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,305 @@
+//! Only the source art is kept in the asset directory. Bevy's asset processor (the `process`
+//! feature) turns it into what the game loads, written to [PROCESSED_DIR]: images are downscaled
+//! and recompressed, and the button states are stitched into one atlas. How each image is
+//! processed is set in the `.meta` file next to it, e.g.
+//!
+//! ```ron
+//! (
+//!     meta_format_version: "1.0",
+//!     asset: Process(
+//!         processor: "LoadTransformAndSave<ImageLoader, Downscale, PngSaver>",
+//!         settings: (
+//!             loader_settings: (
+//!                 format: FromExtension,
+//!                 is_srgb: true,
+//!                 sampler: Default,
+//!                 asset_usage: ("MAIN_WORLD | RENDER_WORLD"),
+//!             ),
+//!             transformer_settings: (max_size: Some(1024)),
+//!             saver_settings: (),
+//!         ),
+//!     ),
+//! )
+//! ```
+//!
+//! Images without one are only recompressed. Without the feature the game loads the processed
+//! assets when there are any, and the source art otherwise.
+
+use bevy::{
+    asset::{
+        AssetLoader, LoadContext, LoadDirectError, RenderAssetUsages,
+        io::{Reader, Writer},
+        processor::LoadTransformAndSave,
+        saver::{AssetSaver, SavedAsset},
+        transformer::{AssetTransformer, TransformedAsset},
+    },
+    image::{
+        ImageFormat, ImageFormatSetting, ImageLoader, ImageLoaderSettings, IntoDynamicImageError,
+    },
+    prelude::*,
+    tasks::futures_lite::AsyncWriteExt,
+};
+use image::{
+    RgbaImage,
+    codecs::png::{CompressionType, FilterType as PngFilterType, PngEncoder},
+    imageops::{self, FilterType},
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Default [AssetPlugin::processed_file_path].
+pub const PROCESSED_DIR: &str = "imported_assets/Default";
+
+/// Processes images, e.g. `misc/rev2/Alliance.png`.
+pub type ImageProcessor = LoadTransformAndSave<ImageLoader, Downscale, PngSaver>;
+/// Processes `*.stitch.ron` files into images.
+pub type StitchProcessor = LoadTransformAndSave<StitchLoader, Downscale, PngSaver>;
+
+pub struct Plugin;
+
+impl bevy::prelude::Plugin for Plugin {
+    fn build(
+        &self,
+        app: &mut App,
+    ) {
+        // Processors are only registered when the processor runs, i.e. with the `process` feature.
+        app.init_asset_loader::<StitchLoader>()
+            .register_asset_processor(ImageProcessor::new(Downscale, PngSaver))
+            .register_asset_processor(StitchProcessor::new(Downscale, PngSaver))
+            .set_default_asset_processor::<ImageProcessor>("png")
+            .set_default_asset_processor::<StitchProcessor>("stitch.ron");
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ProcessError {
+    #[error("Could not convert image: {0}")]
+    Convert(#[from] IntoDynamicImageError),
+    #[error("Could not encode image: {0}")]
+    Encode(#[from] image::ImageError),
+    #[error("Could not write image: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Downscales images to fit [DownscaleSettings::max_size], keeping their aspect ratio.
+#[derive(Default, TypePath)]
+pub struct Downscale;
+
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub struct DownscaleSettings {
+    /// Longest side of the image (pixels). Smaller images are left as they are.
+    pub max_size: Option<u32>,
+}
+
+impl AssetTransformer for Downscale {
+    type AssetInput = Image;
+    type AssetOutput = Image;
+    type Settings = DownscaleSettings;
+    type Error = ProcessError;
+
+    async fn transform<'a>(
+        &'a self,
+        mut asset: TransformedAsset<Image>,
+        settings: &'a DownscaleSettings,
+    ) -> Result<TransformedAsset<Image>, ProcessError> {
+        let image = asset.get_mut();
+
+        let Some(max_size) = settings.max_size else {
+            return Ok(asset);
+        };
+
+        if image.width().max(image.height()) <= max_size {
+            return Ok(asset);
+        }
+
+        let is_srgb = image.texture_descriptor.format.is_srgb();
+        let resized =
+            image
+                .clone()
+                .try_into_dynamic()?
+                .resize(max_size, max_size, FilterType::Lanczos3);
+
+        *image = Image {
+            sampler: image.sampler.clone(),
+            ..Image::from_dynamic(resized, is_srgb, image.asset_usage)
+        };
+
+        Ok(asset)
+    }
+}
+
+/// Saves images as PNG at the best compression, to be loaded by [ImageLoader] with the settings
+/// they were loaded with.
+#[derive(Default, TypePath)]
+pub struct PngSaver;
+
+impl AssetSaver for PngSaver {
+    type Asset = Image;
+    type Settings = ();
+    type OutputLoader = ImageLoader;
+    type Error = ProcessError;
+
+    async fn save(
+        &self,
+        writer: &mut Writer,
+        asset: SavedAsset<'_, Image>,
+        _settings: &(),
+    ) -> Result<ImageLoaderSettings, ProcessError> {
+        let image = asset.get();
+
+        let mut bytes = Vec::new();
+        image
+            .clone()
+            .try_into_dynamic()?
+            .write_with_encoder(PngEncoder::new_with_quality(
+                &mut bytes,
+                CompressionType::Best,
+                PngFilterType::Adaptive,
+            ))?;
+        writer.write_all(&bytes).await?;
+
+        Ok(ImageLoaderSettings {
+            format: ImageFormatSetting::Format(ImageFormat::Png),
+            is_srgb: image.texture_descriptor.format.is_srgb(),
+            sampler: image.sampler.clone(),
+            asset_usage: image.asset_usage,
+            ..default()
+        })
+    }
+}
+
+/// Images laid out side by side, left to right, e.g. the states of a button as an atlas.
+#[derive(Deserialize, Debug)]
+struct Stitch {
+    images: Vec<String>,
+}
+
+/// Loads `*.stitch.ron` files as a single image, see [Stitch].
+#[derive(Default, TypePath)]
+pub struct StitchLoader;
+
+#[derive(Debug, Error)]
+pub enum StitchLoaderError {
+    #[error("Could not read stitch: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not parse stitch: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+    #[error("Could not load stitched image: {0}")]
+    Load(#[from] Box<LoadDirectError>),
+    #[error("Could not convert stitched image: {0}")]
+    Convert(#[from] IntoDynamicImageError),
+}
+
+impl AssetLoader for StitchLoader {
+    type Asset = Image;
+    type Settings = ();
+    type Error = StitchLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let stitch: Stitch = ron::de::from_bytes(&bytes)?;
+
+        let mut parts = Vec::new();
+
+        for path in stitch.images {
+            let part = load_context
+                .loader()
+                .immediate()
+                .load::<Image>(path)
+                .await
+                .map_err(Box::new)?;
+
+            parts.push(part.take().try_into_dynamic()?.to_rgba8());
+        }
+
+        let width = parts.iter().map(|part| part.width()).sum();
+        let height = parts
+            .iter()
+            .map(|part| part.height())
+            .max()
+            .unwrap_or_default();
+        let mut stitched = RgbaImage::new(width, height);
+        let mut x = 0;
+
+        for part in &parts {
+            imageops::replace(&mut stitched, part, x, 0);
+            x += part.width() as i64;
+        }
+
+        Ok(Image::from_dynamic(
+            stitched.into(),
+            true,
+            RenderAssetUsages::default(),
+        ))
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["stitch.ron"]
+    }
+}
+
+/// Processes the source art into [PROCESSED_DIR] without opening a window, returning once every
+/// asset is done. The game's own loaders are registered so that its other assets are copied along.
+#[cfg(feature = "process")]
+pub fn run() {
+    use crate::plugin::{
+        locale::{Catalog, CatalogLoader},
+        scene::in_game::{
+            atlas::{Atlas, AtlasLoader},
+            theme::{Theme, ThemeLoader},
+        },
+    };
+    use bevy::{
+        asset::{
+            AssetMode,
+            processor::{AssetProcessor, ProcessorState},
+        },
+        audio::{AudioLoader, AudioSource},
+        image::{CompressedImageFormats, ImagePlugin},
+        log::LogPlugin,
+        tasks::block_on,
+        text::{Font, FontLoader},
+    };
+    use std::time::Duration;
+
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        LogPlugin::default(),
+        AssetPlugin {
+            file_path: "asset".to_string(),
+            processed_file_path: PROCESSED_DIR.to_string(),
+            mode: AssetMode::Processed,
+            ..default()
+        },
+        ImagePlugin::default(),
+        Plugin,
+    ))
+    .register_asset_loader(ImageLoader::new(CompressedImageFormats::NONE))
+    .init_asset::<Font>()
+    .init_asset_loader::<FontLoader>()
+    .init_asset::<AudioSource>()
+    .init_asset_loader::<AudioLoader>()
+    .init_asset::<Catalog>()
+    .init_asset_loader::<CatalogLoader>()
+    .init_asset::<Theme>()
+    .init_asset_loader::<ThemeLoader>()
+    .init_asset::<Atlas>()
+    .init_asset_loader::<AtlasLoader>();
+
+    app.finish();
+    app.cleanup();
+
+    let processor = app.world().resource::<AssetProcessor>().clone();
+
+    while block_on(processor.get_state()) != ProcessorState::Finished {
+        app.update();
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
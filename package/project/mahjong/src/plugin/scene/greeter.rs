@@ -1,6 +1,9 @@
 use std::time::Duration;
 
-use crate::plugin::scene::Startup;
+use crate::plugin::{
+    frame_budget::FrameBudget,
+    scene::{Startup, loading::Manifest},
+};
 use bevy::prelude::*;
 
 pub struct Plugin;
@@ -37,7 +40,7 @@ struct Marker;
 
 fn on_enter(
     mut commands: Commands,
-    asset_server: Res<AssetServer>,
+    manifest: Res<Manifest>,
     mut timer: ResMut<TimerRes>,
     projection: Query<&Projection, With<Camera>>,
 ) {
@@ -46,15 +49,13 @@ fn on_enter(
         panic!();
     };
 
-    let greeter_asset: Handle<Image> = asset_server.load("misc/rev2/original/Greeter.png");
-
     commands.spawn((
         DespawnOnExit(Startup::Greeter),
         Marker,
         BackgroundSprite,
         Sprite {
             custom_size: Some(Vec2::new(projection.area.width(), projection.area.height())),
-            ..Sprite::from_image(greeter_asset)
+            ..Sprite::from_image(manifest.greeter.clone())
         },
     ));
 
@@ -68,7 +69,9 @@ fn on_update(
     mut next_state: ResMut<NextState<Startup>>,
     mut transform: Query<&mut Sprite, With<BackgroundSprite>>,
     projection: Query<&Projection, With<Camera>>,
+    mut frame_budget: ResMut<FrameBudget>,
 ) {
+    frame_budget.request("greeter");
     timer.inner.tick(time.delta());
 
     if timer.inner.just_finished() {
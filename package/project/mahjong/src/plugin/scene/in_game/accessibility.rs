@@ -0,0 +1,85 @@
+use accesskit::{Node, Role};
+use bevy::{a11y::AccessibilityNode, prelude::*};
+
+use super::{button, marker, memory, theme, tile};
+use crate::plugin::locale::Tr;
+
+pub struct Plugin;
+
+impl bevy::prelude::Plugin for Plugin {
+    fn build(
+        &self,
+        app: &mut App,
+    ) {
+        app.add_observer(hide_tile)
+            .add_observer(show_tile)
+            .add_systems(Update, (label_buttons, label_tiles));
+    }
+}
+
+/// Accessible node of a tile, which [label_tiles] names after its face.
+pub fn tile() -> AccessibilityNode {
+    AccessibilityNode(Node::new(Role::Button))
+}
+
+fn hide_tile(
+    on_add: On<Add, marker::Hidden>,
+    mut nodes: Query<&mut AccessibilityNode>,
+) {
+    if let Ok(mut node) = nodes.get_mut(on_add.entity) {
+        node.set_hidden();
+    }
+}
+
+fn show_tile(
+    on_remove: On<Remove, marker::Hidden>,
+    mut nodes: Query<&mut AccessibilityNode>,
+) {
+    if let Ok(mut node) = nodes.get_mut(on_remove.entity) {
+        node.clear_hidden();
+    }
+}
+
+/// `bevy_ui` labels buttons with their text, which reads poorly for e.g. "[U]ndo". Replace it once
+/// the node has been added, and whenever the language changes.
+fn label_buttons(
+    mut tr: Tr,
+    mut buttons: Query<(&mut AccessibilityNode, &button::Marker), With<Button>>,
+) {
+    let changed = tr.changed();
+
+    for (mut node, marker) in &mut buttons {
+        if changed || node.is_added() {
+            node.set_label(tr.get(marker.label_key()));
+        }
+    }
+}
+
+/// Tile which [label_tiles] names, i.e. any but those [memory] keeps face down.
+type LabelledTile = (With<tile::Marker<0>>, Without<memory::FaceDown>);
+
+/// Names tiles after their face in the active theme once the node has been added, and whenever the
+/// language or theme changes. Face down tiles are left to [memory].
+fn label_tiles(
+    mut tr: Tr,
+    theme: theme::Active,
+    mut labelled: Local<Option<AssetId<theme::Theme>>>,
+    mut tiles: Query<(&mut AccessibilityNode, &tile::Variant), LabelledTile>,
+) {
+    // Read the language change even while the theme is loading, so it is not missed.
+    if tr.changed() {
+        *labelled = None;
+    }
+
+    if !theme.is_loaded() {
+        return;
+    }
+
+    let changed = labelled.replace(theme.id()) != Some(theme.id());
+
+    for (mut node, variant) in &mut tiles {
+        if changed || node.is_added() {
+            node.set_label(tr.get(theme.get().face_key(*variant)));
+        }
+    }
+}
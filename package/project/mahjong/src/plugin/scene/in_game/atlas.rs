@@ -0,0 +1,433 @@
+use super::theme::{Art, Theme, Themes};
+use crate::plugin::scene::loading::Manifest;
+use bevy::{
+    asset::{AssetLoader, LoadContext, RenderAssetUsages, io::Reader},
+    ecs::system::SystemParam,
+    image::TextureAccessError,
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+};
+use serde::Deserialize;
+use thiserror::Error;
+
+pub struct Plugin;
+
+impl bevy::prelude::Plugin for Plugin {
+    fn build(
+        &self,
+        app: &mut App,
+    ) {
+        app.init_asset::<Atlas>()
+            .init_asset_loader::<AtlasLoader>()
+            .init_resource::<FaceAtlas>()
+            .add_systems(Update, bake);
+    }
+}
+
+/// Height of a baked face (pixels). Faces are drawn far smaller than the images they are made of,
+/// so there is no point in keeping those at full size.
+const FACE_HEIGHT: u32 = 256;
+/// Gap between baked faces (pixels), so that filtering does not bleed one into the next.
+const PADDING: u32 = 2;
+
+/// A sheet of equally sized cells, such as the ones laid out by `tool/krita_atlas_helper.py`.
+/// Loaded from `*.atlas.ron` files, which load the sheet itself along with them.
+#[derive(Asset, TypePath, Debug)]
+pub struct Atlas {
+    pub image: Handle<Image>,
+    pub layout: Handle<TextureAtlasLayout>,
+}
+
+/// Contents of an `*.atlas.ron` file. Mirrors the arguments of `create_tiled_layers_with_masks`,
+/// except that the grid need not be square.
+#[derive(Deserialize, Debug)]
+struct AtlasMeta {
+    image: String,
+    /// Side length of a cell (pixels).
+    tile_size: u32,
+    columns: u32,
+    rows: u32,
+    /// Top left corner of the first cell (pixels).
+    #[serde(default)]
+    offset: UVec2,
+    /// Gap between cells (pixels).
+    #[serde(default)]
+    padding: u32,
+}
+
+#[derive(Default, TypePath)]
+pub struct AtlasLoader;
+
+#[derive(Debug, Error)]
+pub enum AtlasLoaderError {
+    #[error("Could not read atlas: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not parse atlas: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+}
+
+impl AssetLoader for AtlasLoader {
+    type Asset = Atlas;
+    type Settings = ();
+    type Error = AtlasLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let meta: AtlasMeta = ron::de::from_bytes(&bytes)?;
+
+        let layout = TextureAtlasLayout::from_grid(
+            UVec2::splat(meta.tile_size),
+            meta.columns,
+            meta.rows,
+            Some(UVec2::splat(meta.padding)),
+            Some(meta.offset),
+        );
+
+        Ok(Atlas {
+            image: load_context.load(meta.image),
+            layout: load_context.add_labeled_asset("layout".to_string(), layout),
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["atlas.ron"]
+    }
+}
+
+/// What the face of a [super::tile::Variant] is drawn with: a single cell of an atlas.
+#[derive(Clone, Debug)]
+pub struct FaceSprite {
+    pub image: Handle<Image>,
+    pub atlas: TextureAtlas,
+    /// Fraction of the tile's face covered.
+    pub size: f32,
+}
+
+/// The faces of the active [Theme], baked into a single image so that each tile draws its face
+/// with one sprite. [Art::Atlas] faces already are, and are used as is. [Art::Text] faces are not
+/// baked at all.
+#[derive(Resource, Default)]
+pub struct FaceAtlas {
+    /// Theme the faces were baked for.
+    theme: Option<AssetId<Theme>>,
+    /// Indexed by [super::tile::Variant].
+    faces: Vec<Option<FaceSprite>>,
+}
+
+impl FaceAtlas {
+    pub fn is_baked(
+        &self,
+        theme: AssetId<Theme>,
+    ) -> bool {
+        self.theme == Some(theme)
+    }
+
+    pub fn get(
+        &self,
+        variant: u32,
+    ) -> Option<&FaceSprite> {
+        self.faces.get(variant as usize)?.as_ref()
+    }
+}
+
+/// Pixels being drawn on, with premultiplied alpha in linear space.
+struct Canvas {
+    size: UVec2,
+    pixels: Vec<Vec4>,
+}
+
+impl Canvas {
+    fn new(size: UVec2) -> Self {
+        Self {
+            size,
+            pixels: vec![Vec4::ZERO; (size.x * size.y) as usize],
+        }
+    }
+
+    /// Draws `source` stretched over `rect` (pixels), tinted by `color` and cut off at `clip`.
+    fn draw(
+        &mut self,
+        source: &Image,
+        rect: Rect,
+        clip: URect,
+        color: Color,
+    ) -> Result<(), TextureAccessError> {
+        let area = rect.intersect(clip.as_rect());
+
+        if area.is_empty() {
+            return Ok(());
+        }
+
+        let tint = color.to_linear().to_vec4();
+        let scale = source.size().as_vec2() / rect.size();
+
+        for y in area.min.y.floor() as u32..area.max.y.ceil() as u32 {
+            for x in area.min.x.floor() as u32..area.max.x.ceil() as u32 {
+                let at = (Vec2::new(x as f32, y as f32) + 0.5 - rect.min) * scale - 0.5;
+                let sample = premultiply(sample(source, at)? * tint);
+                let pixel = &mut self.pixels[(y * self.size.x + x) as usize];
+
+                *pixel = sample + *pixel * (1.0 - sample.w);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn into_image(self) -> Image {
+        let mut image = Image::new_fill(
+            Extent3d {
+                width: self.size.x,
+                height: self.size.y,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            &[0, 0, 0, 0],
+            TextureFormat::Rgba8UnormSrgb,
+            RenderAssetUsages::RENDER_WORLD,
+        );
+
+        for (index, pixel) in self.pixels.into_iter().enumerate() {
+            let rgb = if pixel.w > 0.0 {
+                pixel.truncate() / pixel.w
+            } else {
+                Vec3::ZERO
+            };
+            let color = LinearRgba::from_vec4(rgb.extend(pixel.w));
+            let index = index as u32;
+
+            image
+                .set_color_at(index % self.size.x, index / self.size.x, color.into())
+                .expect("Canvas image has a colour format");
+        }
+
+        image
+    }
+}
+
+fn premultiply(color: Vec4) -> Vec4 {
+    (color.truncate() * color.w).extend(color.w)
+}
+
+/// Colour of `source` at `at` (pixels, from the centre of the top left pixel), filtered linearly.
+fn sample(
+    source: &Image,
+    at: Vec2,
+) -> Result<Vec4, TextureAccessError> {
+    let max = source.size().as_ivec2() - 1;
+    let base = at.floor();
+    let t = at - base;
+
+    let texel = |offset: IVec2| -> Result<Vec4, TextureAccessError> {
+        let at = (base.as_ivec2() + offset)
+            .clamp(IVec2::ZERO, max)
+            .as_uvec2();
+        let color = source.get_color_at(at.x, at.y)?.to_linear().to_vec4();
+
+        Ok(premultiply(color))
+    };
+
+    let top = texel(IVec2::ZERO)?.lerp(texel(IVec2::X)?, t.x);
+    let bottom = texel(IVec2::Y)?.lerp(texel(IVec2::ONE)?, t.x);
+    let color = top.lerp(bottom, t.y);
+
+    // Undone once tinted, see [Canvas::draw].
+    Ok(if color.w > 0.0 {
+        (color.truncate() / color.w).extend(color.w)
+    } else {
+        Vec4::ZERO
+    })
+}
+
+/// Where on a cell [draw_art] draws an image, and in what colour.
+struct Placement {
+    /// Fraction of the cell from its middle, up being positive like in the world.
+    centre: Vec2,
+    /// Fraction of the cell.
+    size: Vec2,
+    color: Option<Color>,
+}
+
+/// Draws `image` on `cell` as `placement` says.
+fn draw_art(
+    canvas: &mut Canvas,
+    images: &Assets<Image>,
+    manifest: &Manifest,
+    cell: URect,
+    image: &str,
+    placement: Placement,
+) {
+    let Placement {
+        centre,
+        size,
+        color,
+    } = placement;
+
+    let Some(source) = images.get(&manifest.image(image)) else {
+        warn!("Image \"{image}\" is missing, its face is left blank!");
+        return;
+    };
+
+    let cell_size = cell.size().as_vec2();
+    let centre = cell.as_rect().center() + Vec2::new(centre.x, -centre.y) * cell_size;
+    let rect = Rect::from_center_size(centre, size * cell_size);
+
+    if let Err(error) = canvas.draw(source, rect, cell, color.unwrap_or_default()) {
+        warn!("Could not bake image \"{image}\": {error}");
+    }
+}
+
+/// Bakes the faces of the active theme once it and its images are loaded, including after
+/// switching themes or editing the active one.
+/// The active theme, and what happened to it on disk.
+#[derive(SystemParam)]
+struct ActiveTheme<'w, 's> {
+    themes: Res<'w, Themes>,
+    assets: Res<'w, Assets<Theme>>,
+    events: MessageReader<'w, 's, AssetEvent<Theme>>,
+}
+
+fn bake(
+    active: ActiveTheme,
+    manifest: Res<Manifest>,
+    atlases: Res<Assets<Atlas>>,
+    mut images: ResMut<Assets<Image>>,
+    mut layouts: ResMut<Assets<TextureAtlasLayout>>,
+    mut face_atlas: ResMut<FaceAtlas>,
+) {
+    let ActiveTheme {
+        themes,
+        assets,
+        events: mut theme_events,
+    } = active;
+    let id = themes.active().id();
+
+    if theme_events.read().any(|event| event.is_modified(id)) {
+        face_atlas.theme = None;
+    }
+
+    if face_atlas.is_baked(id) {
+        return;
+    }
+
+    let Some(theme) = assets.get(id) else {
+        return;
+    };
+
+    if !theme.is_preloaded(&manifest) {
+        return;
+    }
+
+    let baked: Vec<usize> = theme
+        .faces
+        .iter()
+        .enumerate()
+        .filter(|(_, face)| matches!(face.art, Art::Image { .. } | Art::Pips { .. }))
+        .map(|(index, _)| index)
+        .collect();
+
+    let face_size = theme.tile.face_size.as_vec2();
+    let cell_size = UVec2::new(
+        (FACE_HEIGHT as f32 * face_size.x / face_size.y).round() as u32,
+        FACE_HEIGHT,
+    );
+    let columns = (baked.len() as f32).sqrt().ceil().max(1.0) as u32;
+    let rows = (baked.len() as u32).div_ceil(columns).max(1);
+    let layout =
+        TextureAtlasLayout::from_grid(cell_size, columns, rows, Some(UVec2::splat(PADDING)), None);
+
+    let size = layout.size;
+    let mut canvas = Canvas::new(size);
+
+    for (cell, index) in baked.iter().enumerate() {
+        let cell = layout.textures[cell];
+
+        match &theme.faces[*index].art {
+            Art::Image { image, size } => {
+                draw_art(
+                    &mut canvas,
+                    &images,
+                    &manifest,
+                    cell,
+                    image,
+                    Placement {
+                        centre: Vec2::ZERO,
+                        size: Vec2::splat(*size),
+                        color: None,
+                    },
+                );
+            },
+            Art::Pips { image, pips } => {
+                for pip in pips {
+                    draw_art(
+                        &mut canvas,
+                        &images,
+                        &manifest,
+                        cell,
+                        image,
+                        Placement {
+                            centre: Vec2::new(pip.x, pip.y),
+                            size: Vec2::splat(pip.size),
+                            color: pip.color,
+                        },
+                    );
+                }
+            },
+            Art::Atlas { .. } | Art::Text { .. } => unreachable!(),
+        }
+    }
+
+    let image = images.add(canvas.into_image());
+    let layout = layouts.add(layout);
+
+    let faces = theme
+        .faces
+        .iter()
+        .enumerate()
+        .map(|(index, face)| match &face.art {
+            Art::Image { .. } | Art::Pips { .. } => Some(FaceSprite {
+                image: image.clone(),
+                atlas: TextureAtlas {
+                    layout: layout.clone(),
+                    index: baked.binary_search(&index).ok()?,
+                },
+                size: 1.0,
+            }),
+            Art::Atlas { atlas, index, size } => {
+                let Some(atlas) = atlases.get(&manifest.atlas(atlas)) else {
+                    warn!("Atlas \"{atlas}\" is missing, its face is left blank!");
+                    return None;
+                };
+
+                Some(FaceSprite {
+                    image: atlas.image.clone(),
+                    atlas: TextureAtlas {
+                        layout: atlas.layout.clone(),
+                        index: *index,
+                    },
+                    size: *size,
+                })
+            },
+            Art::Text { .. } => None,
+        })
+        .collect();
+
+    info!(
+        "Baked {} faces of theme \"{}\" into a {}x{} atlas",
+        baked.len(),
+        theme.name,
+        size.x,
+        size.y,
+    );
+
+    *face_atlas = FaceAtlas {
+        theme: Some(id),
+        faces,
+    };
+}
@@ -0,0 +1,175 @@
+use bevy::{platform::collections::HashSet, prelude::*};
+
+use super::{free, marker, players, tile};
+
+pub struct Plugin;
+
+impl bevy::prelude::Plugin for Plugin {
+    fn build(
+        &self,
+        app: &mut App,
+    ) {
+        app.init_resource::<BoardIndex>()
+            .init_resource::<Despawning>()
+            .add_observer(hide_tile)
+            .add_observer(show_tile)
+            .add_observer(despawn_tile);
+    }
+}
+
+/// Side length of a tile, in [tile::Position] units.
+const TGS: u32 = tile::PositionGenerator::<tile::Turtle>::TILE_GRID_SIZE as u32;
+
+/// Positions of the tiles still in play on a board, keyed by layer, row and column. The tiles around
+/// a position are looked up directly, rather than by going through the whole board.
+#[derive(Clone, Default, Debug)]
+pub struct Board {
+    tiles: HashSet<tile::Position>,
+    /// Highest layer any tile was ever on.
+    top: u32,
+}
+
+impl Board {
+    pub fn insert(
+        &mut self,
+        position: tile::Position,
+    ) {
+        self.top = self.top.max(position.z);
+        self.tiles.insert(position);
+    }
+
+    pub fn remove(
+        &mut self,
+        position: &tile::Position,
+    ) {
+        self.tiles.remove(position);
+    }
+
+    pub fn contains(
+        &self,
+        position: &tile::Position,
+    ) -> bool {
+        self.tiles.contains(position)
+    }
+
+    pub fn len(&self) -> usize {
+        self.tiles.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &tile::Position> {
+        self.tiles.iter()
+    }
+
+    /// Tiles at column `x` of layer `z` which share (part of) a row with a tile at row `y`.
+    fn column(
+        &self,
+        x: Option<u32>,
+        y: u32,
+        z: u32,
+    ) -> impl Iterator<Item = tile::Position> {
+        x.into_iter()
+            .flat_map(move |x| {
+                (y.saturating_sub(TGS - 1)..y + TGS)
+                    .map(move |y| tile::Position(UVec3::new(x, y, z)))
+            })
+            .filter(|position| self.contains(position))
+    }
+
+    /// Tiles directly next to `position` on `side`.
+    pub fn neighbours(
+        &self,
+        position: &tile::Position,
+        side: free::Side,
+    ) -> impl Iterator<Item = tile::Position> {
+        let x = match side {
+            free::Side::Left => position.x.checked_sub(TGS),
+            free::Side::Right => Some(position.x + TGS),
+        };
+
+        self.column(x, position.y, position.z)
+    }
+
+    /// Tiles lying (partly) on top of `position`, on any higher layer.
+    pub fn above(
+        &self,
+        position: &tile::Position,
+    ) -> impl Iterator<Item = tile::Position> {
+        let position = *position;
+
+        (position.z + 1..=self.top).flat_map(move |z| {
+            (position.x.saturating_sub(TGS - 1)..position.x + TGS)
+                .flat_map(move |x| self.column(Some(x), position.y, z))
+        })
+    }
+}
+
+impl FromIterator<tile::Position> for Board {
+    fn from_iter<T: IntoIterator<Item = tile::Position>>(iter: T) -> Self {
+        let mut board = Board::default();
+
+        for position in iter {
+            board.insert(position);
+        }
+
+        board
+    }
+}
+
+/// [Board] of each [players::Player]. Tiles enter it as they are dealt, leave it with
+/// [marker::Hidden] when removed, and come back when that is undone.
+#[derive(Resource, Default, Debug)]
+pub struct BoardIndex([Board; players::PLAYERS]);
+
+impl BoardIndex {
+    pub fn get(
+        &self,
+        player: players::Player,
+    ) -> &Board {
+        &self.0[player.0]
+    }
+}
+
+/// Removed tiles being despawned, which lose [marker::Hidden] on the way out without coming back.
+#[derive(Resource, Default)]
+struct Despawning(HashSet<Entity>);
+
+fn hide_tile(
+    on_add: On<Add, marker::Hidden>,
+    tiles: Query<(&tile::Position, &players::Player), With<tile::Marker<0>>>,
+    mut index: ResMut<BoardIndex>,
+) {
+    if let Ok((position, player)) = tiles.get(on_add.entity) {
+        index.0[player.0].remove(position);
+    }
+}
+
+fn show_tile(
+    on_remove: On<Remove, marker::Hidden>,
+    tiles: Query<(&tile::Position, &players::Player), With<tile::Marker<0>>>,
+    mut index: ResMut<BoardIndex>,
+    mut despawning: ResMut<Despawning>,
+) {
+    if despawning.0.remove(&on_remove.entity) {
+        return;
+    }
+
+    if let Ok((position, player)) = tiles.get(on_remove.entity) {
+        index.0[player.0].insert(*position);
+    }
+}
+
+/// Tiles leave the index when despawned, e.g. along with the rest of the board.
+fn despawn_tile(
+    on_despawn: On<Despawn, tile::Marker<0>>,
+    tiles: Query<(&tile::Position, &players::Player, Has<marker::Hidden>)>,
+    mut index: ResMut<BoardIndex>,
+    mut despawning: ResMut<Despawning>,
+) {
+    match tiles.get(on_despawn.entity) {
+        Ok((_, _, true)) => {
+            despawning.0.insert(on_despawn.entity);
+        },
+        Ok((position, player, false)) => index.0[player.0].remove(position),
+        Err(_) => {},
+    }
+}
@@ -0,0 +1,570 @@
+use crate::plugin::{
+    frame_budget::FrameBudget,
+    locale,
+    scene::main_menu::settings::{AppExt, Setting},
+};
+use bevy::{
+    asset::uuid::Uuid,
+    camera::NormalizedRenderTarget,
+    ecs::system::SystemParam,
+    picking::{
+        backend::HitData,
+        pointer::{Location, PointerButton, PointerId},
+    },
+    prelude::*,
+};
+use rand::{
+    Rng, SeedableRng,
+    rngs::StdRng,
+    seq::{IteratorRandom, SliceRandom},
+};
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
+
+use super::{InGame, InPlay, SelectedTile, board::Board, memory, players, rules, tile};
+
+pub struct Plugin;
+
+impl bevy::prelude::Plugin for Plugin {
+    fn build(
+        &self,
+        app: &mut App,
+    ) {
+        app.init_resource::<AutoPlay>()
+            .add_systems(OnEnter(InGame::Root), |mut auto_play: ResMut<AutoPlay>| {
+                auto_play.pending = None;
+                auto_play.plan.clear();
+                auto_play.unsolved = None;
+            })
+            .add_systems(Update, play.run_if(in_state(InGame::Running)))
+            .add_setting(Setting {
+                label: "settings.autoplay",
+                value: |world| {
+                    locale::translate(world, world.resource::<AutoPlay>().strategy.key())
+                },
+                adjust: |world, delta| {
+                    let mut auto_play = world.resource_mut::<AutoPlay>();
+                    auto_play.strategy = auto_play.strategy.step(delta);
+                },
+            })
+            .add_setting(Setting {
+                label: "settings.autoplay_pace",
+                value: |world| locale::translate(world, world.resource::<AutoPlay>().pace.key()),
+                adjust: |world, delta| {
+                    let mut auto_play = world.resource_mut::<AutoPlay>();
+                    auto_play.pace = auto_play.pace.step(delta);
+                    let duration = auto_play.pace.duration();
+                    auto_play.timer.set_duration(duration);
+                },
+            });
+    }
+}
+
+/// Positions searched by [Strategy::Lookahead] before settling for a [Strategy::Greedy] move.
+pub const BUDGET: usize = 2000;
+
+/// Positions searched by each attempt of [solve].
+const ATTEMPT: usize = 100;
+
+/// Pointer which the bot presses tiles with.
+const POINTER: PointerId = PointerId::Custom(Uuid::from_u128(0x6d61_686a_6f6e_6762_6f74));
+
+/// How the bot picks the next pair.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum Strategy {
+    #[default]
+    Off,
+    /// Any pair which can be removed.
+    Random,
+    /// The pair highest up in the stack, which uncovers the most.
+    Greedy,
+    /// Searches for a way to clear the whole board, see [solve].
+    Lookahead,
+}
+
+impl Strategy {
+    pub const ALL: [Strategy; 4] = [
+        Strategy::Off,
+        Strategy::Random,
+        Strategy::Greedy,
+        Strategy::Lookahead,
+    ];
+
+    /// Message key of the name.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Strategy::Off => "autoplay.off",
+            Strategy::Random => "autoplay.random",
+            Strategy::Greedy => "autoplay.greedy",
+            Strategy::Lookahead => "autoplay.lookahead",
+        }
+    }
+
+    fn step(
+        &self,
+        delta: i32,
+    ) -> Self {
+        let index = Self::ALL
+            .iter()
+            .position(|strategy| strategy == self)
+            .unwrap() as i32;
+        Self::ALL[(index + delta).rem_euclid(Self::ALL.len() as i32) as usize]
+    }
+
+    /// Indices into `board` of the next pair to remove, if any. [Strategy::Lookahead] searches anew
+    /// on every call, see [AutoPlay] for how the game keeps the plan instead.
+    pub fn choose(
+        &self,
+        board: &[(tile::Position, tile::Variant)],
+        rules: &dyn rules::RuleSet,
+        rng: &mut impl Rng,
+    ) -> Option<(usize, usize)> {
+        let removed = vec![false; board.len()];
+
+        match self {
+            Strategy::Off => None,
+            Strategy::Random => pairs(board, &removed, rules).into_iter().choose(rng),
+            Strategy::Greedy => pairs(board, &removed, rules).first().copied(),
+            Strategy::Lookahead => solve(board, rules, BUDGET)
+                .and_then(|plan| plan.first().copied())
+                .or_else(|| Strategy::Greedy.choose(board, rules, rng)),
+        }
+    }
+}
+
+/// Time between two presses of the bot.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum Pace {
+    Fast,
+    #[default]
+    Normal,
+    Slow,
+}
+
+impl Pace {
+    pub const ALL: [Pace; 3] = [Pace::Fast, Pace::Normal, Pace::Slow];
+
+    /// Message key of the name.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Pace::Fast => "autoplay.fast",
+            Pace::Normal => "autoplay.normal",
+            Pace::Slow => "autoplay.slow",
+        }
+    }
+
+    pub fn duration(&self) -> Duration {
+        match self {
+            Pace::Fast => Duration::from_millis(150),
+            Pace::Normal => Duration::from_millis(500),
+            Pace::Slow => Duration::from_millis(1500),
+        }
+    }
+
+    fn step(
+        &self,
+        delta: i32,
+    ) -> Self {
+        let index = Self::ALL.iter().position(|pace| pace == self).unwrap() as i32;
+        Self::ALL[(index + delta).rem_euclid(Self::ALL.len() as i32) as usize]
+    }
+}
+
+/// The bot, playing alone, or as the second player in multiplayer games. It presses tiles like a
+/// player would, so every move goes through the same observers as a mouse press. In Memory games
+/// it knows the faces.
+#[derive(Resource, Debug)]
+pub struct AutoPlay {
+    strategy: Strategy,
+    pace: Pace,
+    timer: Timer,
+    /// Second tile of the pair being removed.
+    pending: Option<Entity>,
+    /// Pairs left to remove by [Strategy::Lookahead], next one last.
+    plan: Vec<(Entity, Entity)>,
+    /// Tiles left after the last [Strategy::Greedy] move which [Strategy::Lookahead] fell back to.
+    /// The board is not searched again until someone else changes it.
+    unsolved: Option<HashSet<Entity>>,
+}
+
+impl Default for AutoPlay {
+    fn default() -> Self {
+        Self {
+            strategy: Strategy::default(),
+            pace: Pace::default(),
+            timer: Timer::new(Pace::default().duration(), TimerMode::Repeating),
+            pending: None,
+            plan: Vec::new(),
+            unsolved: None,
+        }
+    }
+}
+
+impl AutoPlay {
+    /// Next pair to remove from `board`. The plan of [Strategy::Lookahead] is kept as long as it
+    /// holds, i.e. until someone else plays or undoes a move. So is a failure to find one.
+    fn next(
+        &mut self,
+        board: &[(Entity, tile::Position, tile::Variant)],
+        rules: &dyn rules::RuleSet,
+    ) -> Option<(Entity, Entity)> {
+        let tiles: Vec<_> = board
+            .iter()
+            .map(|(_, position, variant)| (*position, *variant))
+            .collect();
+
+        if self.strategy != Strategy::Lookahead {
+            let (a, b) = self.strategy.choose(&tiles, rules, &mut rand::rng())?;
+            return Some((board[a].0, board[b].0));
+        }
+
+        let positions: Board = tiles.iter().map(|(position, _)| *position).collect();
+        let find = |entity: Entity| board.iter().find(|(other, ..)| *other == entity);
+
+        if let Some(&(a, b)) = self.plan.last()
+            && let (Some((_, pa, va)), Some((_, pb, vb))) = (find(a), find(b))
+            && rules.valid_removal((va, pa), (vb, pb), &positions)
+        {
+            return self.plan.pop();
+        }
+
+        let entities: HashSet<Entity> = board.iter().map(|(entity, ..)| *entity).collect();
+
+        if self.unsolved.as_ref() != Some(&entities)
+            && let Some(plan) = solve(&tiles, rules, BUDGET)
+        {
+            self.unsolved = None;
+            self.plan = plan
+                .into_iter()
+                .rev()
+                .map(|(a, b)| (board[a].0, board[b].0))
+                .collect();
+            return self.plan.pop();
+        }
+
+        self.plan.clear();
+        let (a, b) = Strategy::Greedy.choose(&tiles, rules, &mut rand::rng())?;
+        let (a, b) = (board[a].0, board[b].0);
+        self.unsolved = Some(
+            entities
+                .into_iter()
+                .filter(|entity| *entity != a && *entity != b)
+                .collect(),
+        );
+        Some((a, b))
+    }
+}
+
+/// Pairs which can be removed from `board`, leaving out the `removed` tiles. Ordered by height,
+/// highest first.
+pub fn pairs(
+    board: &[(tile::Position, tile::Variant)],
+    removed: &[bool],
+    rules: &dyn rules::RuleSet,
+) -> Vec<(usize, usize)> {
+    let remaining: Vec<usize> = (0..board.len()).filter(|index| !removed[*index]).collect();
+    let positions: Board = remaining.iter().map(|index| board[*index].0).collect();
+    let free: Vec<usize> = remaining
+        .into_iter()
+        .filter(|index| rules.is_free(&board[*index].0, &positions))
+        .collect();
+
+    let mut pairs = Vec::new();
+
+    for (nth, a) in free.iter().enumerate() {
+        for b in &free[nth + 1..] {
+            let (a_position, a_variant) = &board[*a];
+            let (b_position, b_variant) = &board[*b];
+
+            if a_variant.matches(b_variant)
+                && rules.valid_removal((a_variant, a_position), (b_variant, b_position), &positions)
+            {
+                pairs.push((*a, *b));
+            }
+        }
+    }
+
+    pairs.sort_by_key(|(a, b)| {
+        let (za, zb) = (board[*a].0.z, board[*b].0.z);
+        std::cmp::Reverse((za.max(zb), za + zb))
+    });
+
+    pairs
+}
+
+/// Pairs, as indices into `board`, which clear it when removed in order. Searched depth first from
+/// scratch over and over, each time trying the pairs in another order, since an early mistake is
+/// rarely undone by backtracking alone. Gives up after `budget` positions in total.
+pub fn solve(
+    board: &[(tile::Position, tile::Variant)],
+    rules: &dyn rules::RuleSet,
+    budget: usize,
+) -> Option<Vec<(usize, usize)>> {
+    fn search(
+        board: &[(tile::Position, tile::Variant)],
+        rules: &dyn rules::RuleSet,
+        removed: &mut Vec<bool>,
+        seen: &mut HashSet<Vec<bool>>,
+        budget: &mut usize,
+        rng: &mut StdRng,
+        plan: &mut Vec<(usize, usize)>,
+    ) -> bool {
+        if removed.iter().all(|removed| *removed) {
+            return true;
+        }
+
+        if *budget == 0 || !seen.insert(removed.clone()) {
+            return false;
+        }
+        *budget -= 1;
+
+        let mut candidates = pairs(board, removed, rules);
+        let mut left: HashMap<tile::MatchGroup, (usize, usize)> = HashMap::new();
+
+        for (index, (_, variant)) in board.iter().enumerate() {
+            if !removed[index] {
+                left.entry(variant.match_group()).or_default().0 += 1;
+            }
+        }
+
+        for index in candidates
+            .iter()
+            .flat_map(|(a, b)| [*a, *b])
+            .collect::<HashSet<_>>()
+        {
+            left.get_mut(&board[index].1.match_group()).unwrap().1 += 1;
+        }
+
+        // Removing every tile left of a kind takes nothing away from any other pair, so there is
+        // no need to try anything else.
+        if let Some(&safe) = candidates.iter().find(|(a, _)| {
+            let (count, free) = left[&board[*a].1.match_group()];
+            count == free && count % 2 == 0
+        }) {
+            candidates = vec![safe];
+        } else {
+            candidates.shuffle(rng);
+        }
+
+        for (a, b) in candidates {
+            removed[a] = true;
+            removed[b] = true;
+            plan.push((a, b));
+
+            if search(board, rules, removed, seen, budget, rng, plan) {
+                return true;
+            }
+
+            removed[a] = false;
+            removed[b] = false;
+            plan.pop();
+        }
+
+        false
+    }
+
+    let mut rng = StdRng::seed_from_u64(board.len() as u64);
+    let mut budget = budget;
+
+    while budget > 0 {
+        let mut plan = Vec::new();
+        let mut attempt = budget.min(ATTEMPT);
+        budget -= attempt;
+
+        if search(
+            board,
+            rules,
+            &mut vec![false; board.len()],
+            &mut HashSet::new(),
+            &mut attempt,
+            &mut rng,
+            &mut plan,
+        ) {
+            return Some(plan);
+        }
+    }
+
+    None
+}
+
+/// Board the bot plays on, if it is its turn.
+fn board(players: &players::Players) -> Option<players::Player> {
+    match players.multiplayer() {
+        players::Multiplayer::Off => Some(players::Player(0)),
+        // The board is shared, the second turn is the bot's.
+        players::Multiplayer::HotSeat => (players.turn == 1).then_some(players::Player(0)),
+        players::Multiplayer::Versus => Some(players::Player(1)),
+    }
+}
+
+/// Presses the tile `entity` with the pointer of the bot, as a player would.
+pub fn press(
+    commands: &mut Commands,
+    entity: Entity,
+) {
+    commands.trigger(Pointer::new(
+        POINTER,
+        Location {
+            target: NormalizedRenderTarget::None {
+                width: 0,
+                height: 0,
+            },
+            position: Vec2::ZERO,
+        },
+        Press {
+            button: PointerButton::Primary,
+            hit: HitData::new(Entity::PLACEHOLDER, 0.0, None, None),
+        },
+        entity,
+    ));
+}
+
+type Tile = (
+    Entity,
+    &'static tile::Position,
+    &'static tile::Variant,
+    &'static players::Player,
+);
+
+/// What decides which board is played, and which pairs may be removed from it.
+#[derive(SystemParam)]
+struct Game<'w> {
+    rules: Res<'w, rules::Rules>,
+    players: Res<'w, players::Players>,
+    flip_back: Res<'w, memory::FlipBack>,
+}
+
+fn play(
+    mut commands: Commands,
+    mut auto_play: ResMut<AutoPlay>,
+    time: Res<Time>,
+    tiles: Query<Tile, InPlay>,
+    mut selected_tile: ResMut<SelectedTile>,
+    game: Game,
+    mut frame_budget: ResMut<FrameBudget>,
+) {
+    let Game {
+        rules,
+        players,
+        flip_back,
+    } = game;
+
+    if auto_play.strategy == Strategy::Off {
+        auto_play.pending = None;
+        return;
+    }
+
+    frame_budget.request("auto play");
+
+    if !auto_play.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    let Some(player) = board(&players) else {
+        auto_play.pending = None;
+        return;
+    };
+
+    if flip_back.pending(player) {
+        return;
+    }
+
+    if let Some(entity) = auto_play.pending.take() {
+        if tiles.contains(entity) {
+            press(&mut commands, entity);
+        }
+        return;
+    }
+
+    let board: Vec<_> = tiles
+        .iter()
+        .filter(|(.., other)| **other == player)
+        .map(|(entity, position, variant, _)| (entity, *position, *variant))
+        .collect();
+
+    let Some((a, b)) = auto_play.next(&board, rules.get()) else {
+        return;
+    };
+
+    // Whatever was selected by hand would be paired with the first press.
+    selected_tile[player.0] = None;
+    press(&mut commands, a);
+    auto_play.pending = Some(b);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        super::{generator, rules::RuleSet},
+        *,
+    };
+
+    const SEEDS: u64 = 8;
+
+    /// Two layers, the top one a tile smaller on every side.
+    fn layout() -> Vec<tile::Position> {
+        let layer = |z: u32, columns: std::ops::Range<u32>, rows: std::ops::Range<u32>| {
+            rows.flat_map(move |row| {
+                columns
+                    .clone()
+                    .map(move |column| tile::Position(UVec3::new(column * 2, row * 2, z)))
+            })
+        };
+
+        layer(0, 0..6, 0..4).chain(layer(1, 1..5, 1..3)).collect()
+    }
+
+    fn deal(seed: u64) -> Vec<(tile::Position, tile::Variant)> {
+        generator::generate_solvable_board(layout(), Some(seed)).0
+    }
+
+    #[test]
+    fn pairs_are_free_and_matching() {
+        for seed in 0..SEEDS {
+            let board = deal(seed);
+            let mut removed = vec![false; board.len()];
+
+            for _ in 0..board.len() / 4 {
+                let remaining: Board = (0..board.len())
+                    .filter(|index| !removed[*index])
+                    .map(|index| board[index].0)
+                    .collect();
+                let pairs = pairs(&board, &removed, &rules::Classic);
+
+                assert!(!pairs.is_empty());
+
+                for &(a, b) in &pairs {
+                    assert!(a != b && !removed[a] && !removed[b]);
+                    assert!(board[a].1.matches(&board[b].1));
+                    assert!(rules::Classic.is_free(&board[a].0, &remaining));
+                    assert!(rules::Classic.is_free(&board[b].0, &remaining));
+                }
+
+                let (a, b) = pairs[0];
+                removed[a] = true;
+                removed[b] = true;
+            }
+        }
+    }
+
+    #[test]
+    fn solve_clears_the_board() {
+        for seed in 0..SEEDS {
+            let board = deal(seed);
+            let plan = solve(&board, &rules::Classic, BUDGET).expect("Solved");
+            let mut removed = vec![false; board.len()];
+
+            assert_eq!(plan.len(), board.len() / 2);
+
+            for (a, b) in plan {
+                assert!(pairs(&board, &removed, &rules::Classic).contains(&(a, b)));
+                removed[a] = true;
+                removed[b] = true;
+            }
+
+            assert!(removed.iter().all(|removed| *removed));
+        }
+    }
+}
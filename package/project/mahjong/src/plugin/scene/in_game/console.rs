@@ -0,0 +1,434 @@
+//! Console of cheats and debug commands, toggled with the key left of 1 (see [USAGE]). Commands go
+//! through the same resources, states and tile presses as the game, so that e.g. defeat by running
+//! out of moves can be reached in a few commands. Only compiled in with the `console` feature.
+
+use crate::plugin::{
+    default::OverlayCamera,
+    frame_budget::FrameBudget,
+    platform::{Platform, PlatformTrait},
+};
+use bevy::{
+    input::{
+        ButtonState, InputSystems,
+        keyboard::{Key, KeyboardInput},
+    },
+    prelude::*,
+};
+use std::collections::VecDeque;
+
+use super::{
+    InGame, InPlay, SelectedTile, bot, free, memory,
+    players::{self, Players},
+    rules::{Mode, Rules},
+    tile,
+};
+
+pub struct Plugin;
+
+impl bevy::prelude::Plugin for Plugin {
+    fn build(
+        &self,
+        app: &mut App,
+    ) {
+        app.init_resource::<Console>()
+            .add_systems(bevy::app::Startup, spawn)
+            // Before anything else sees the keys typed into the console.
+            .add_systems(
+                PreUpdate,
+                input
+                    .after(InputSystems)
+                    .run_if(resource_exists::<State<InGame>>),
+            )
+            .add_systems(
+                Update,
+                (
+                    run.run_if(resource_exists::<State<InGame>>),
+                    press.run_if(in_state(InGame::Running)),
+                    show,
+                )
+                    .chain(),
+            );
+    }
+}
+
+const USAGE: &str = "\
+Commands:
+    seed <n>              Deal the board of seed <n>
+    layout <mode>         Deal the same seed in solitaire, shisen-sho or memory
+    win, lose             End the game as the first player
+    reveal                Show the faces of face down tiles, or stop showing them
+    remove-pair <a> <b>   Press tiles #a and #b, see dump-board
+    solve                 Clear the first board, if a way is found
+    state <InGame::...>   Enter a state, e.g. InGame::Defeat
+    dump-board            Log the tiles in play";
+
+type Tile = (
+    Entity,
+    &'static tile::Position,
+    &'static tile::Variant,
+    &'static players::Player,
+);
+
+/// Lines of output shown.
+const LINES: usize = 12;
+
+#[derive(Resource, Default)]
+struct Console {
+    open: bool,
+    input: String,
+    /// Lines entered since the last [run].
+    entered: Vec<String>,
+    output: VecDeque<String>,
+    /// Pairs left to press, next one first.
+    presses: VecDeque<(Entity, Entity)>,
+    /// Second tile of the pair being pressed.
+    pending: Option<Entity>,
+}
+
+impl Console {
+    fn print(
+        &mut self,
+        line: String,
+    ) {
+        info!("{line}");
+
+        if self.output.len() >= LINES {
+            self.output.pop_front();
+        }
+        self.output.push_back(line);
+    }
+}
+
+enum Command {
+    Help,
+    Seed(u64),
+    Layout(Mode),
+    Finish { cleared: bool },
+    Reveal,
+    RemovePair(u32, u32),
+    Solve,
+    State(InGame),
+    DumpBoard,
+}
+
+impl Command {
+    fn parse(line: &str) -> Result<Self, String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let invalid = || format!("Invalid command: {line}\n\n{USAGE}");
+
+        let command = match words.as_slice() {
+            ["help"] => Command::Help,
+            ["seed", seed] => Command::Seed(seed.parse().map_err(|_| invalid())?),
+            ["layout", mode] => Command::Layout(
+                *Mode::ALL
+                    .iter()
+                    .find(|other| name(other.key()) == *mode)
+                    .ok_or_else(invalid)?,
+            ),
+            ["win"] => Command::Finish { cleared: true },
+            ["lose"] => Command::Finish { cleared: false },
+            ["reveal"] => Command::Reveal,
+            ["remove-pair", a, b] => Command::RemovePair(
+                a.trim_start_matches('#').parse().map_err(|_| invalid())?,
+                b.trim_start_matches('#').parse().map_err(|_| invalid())?,
+            ),
+            ["solve"] => Command::Solve,
+            ["state", state] => Command::State(match state.trim_start_matches("InGame::") {
+                "Root" => InGame::Root,
+                "Init" => InGame::Init,
+                "Running" => InGame::Running,
+                "Victory" => InGame::Victory,
+                "Defeat" => InGame::Defeat,
+                _ => return Err(invalid()),
+            }),
+            ["dump-board"] => Command::DumpBoard,
+            _ => return Err(invalid()),
+        };
+
+        Ok(command)
+    }
+
+    /// Runs the command, returning what to print.
+    fn run(
+        self,
+        world: &mut World,
+    ) -> String {
+        let first = players::Player(0);
+
+        match self {
+            Command::Help => USAGE.to_string(),
+            Command::Seed(seed) => {
+                world.resource::<Platform>().rng_seed_set(seed);
+                world.resource_mut::<NextState<InGame>>().set(InGame::Root);
+                format!("Dealing seed {seed}")
+            },
+            Command::Layout(mode) => {
+                // The seed of the current game is dealt again.
+                world.resource_mut::<Rules>().select_mode(mode);
+                world.resource_mut::<NextState<InGame>>().set(InGame::Root);
+                format!("Dealing {mode:?}")
+            },
+            Command::Finish { cleared } => {
+                let state = world.resource_mut::<Players>().finish(first, cleared);
+                world.resource_mut::<NextState<InGame>>().set(state.clone());
+                format!("Entering {state:?}")
+            },
+            Command::Reveal => {
+                let mut reveal = world.resource_mut::<memory::Reveal>();
+                **reveal = !**reveal;
+                format!("Revealed: {}", **reveal)
+            },
+            Command::RemovePair(a, b) => {
+                let find = |world: &mut World, index: u32| {
+                    world
+                        .query_filtered::<Entity, InPlay>()
+                        .iter(world)
+                        .find(|entity| entity.index_u32() == index)
+                };
+
+                let (Some(entity_a), Some(entity_b)) = (find(world, a), find(world, b)) else {
+                    return format!("No tile #{a} and #{b} in play");
+                };
+
+                world
+                    .resource_mut::<Console>()
+                    .presses
+                    .push_back((entity_a, entity_b));
+                format!("Pressing #{a} and #{b}")
+            },
+            Command::Solve => {
+                let board: Vec<(Entity, tile::Position, tile::Variant)> = world
+                    .query_filtered::<Tile, InPlay>()
+                    .iter(world)
+                    .filter(|(.., player)| **player == first)
+                    .map(|(entity, position, variant, _)| (entity, *position, *variant))
+                    .collect();
+                let tiles: Vec<_> = board
+                    .iter()
+                    .map(|(_, position, variant)| (*position, *variant))
+                    .collect();
+                let rules = world.resource::<Rules>().get();
+
+                let Some(plan) = bot::solve(&tiles, rules, bot::BUDGET) else {
+                    return "No way found to clear the board".to_string();
+                };
+
+                let mut console = world.resource_mut::<Console>();
+                console
+                    .presses
+                    .extend(plan.iter().map(|(a, b)| (board[*a].0, board[*b].0)));
+                format!("Removing {} pairs", plan.len())
+            },
+            Command::State(state) => {
+                world.resource_mut::<NextState<InGame>>().set(state.clone());
+                format!("Entering {state:?}")
+            },
+            Command::DumpBoard => {
+                let mut tiles: Vec<_> = world
+                    .query_filtered::<Tile, InPlay>()
+                    .iter(world)
+                    .map(|(entity, position, variant, player)| {
+                        (*player, **position, entity, *variant)
+                    })
+                    .collect();
+                // Top layer first, then row by row.
+                tiles.sort_by_key(|(player, position, ..)| {
+                    (
+                        player.0,
+                        std::cmp::Reverse(position.z),
+                        position.y,
+                        position.x,
+                    )
+                });
+
+                let free_tiles = world.resource::<free::FreeTiles>();
+
+                for (player, position, entity, variant) in &tiles {
+                    let free = if free_tiles.contains(*entity) {
+                        " free"
+                    } else {
+                        ""
+                    };
+                    info!(
+                        "Player {} #{} at {position}: variant {}{free}",
+                        player.0,
+                        entity.index_u32(),
+                        **variant
+                    );
+                }
+
+                format!("Logged {} tiles", tiles.len())
+            },
+        }
+    }
+}
+
+/// Name of a [Mode] in commands, e.g. `shisen-sho` for `mode.shisen_sho`.
+fn name(key: &str) -> String {
+    key.trim_start_matches("mode.").replace('_', "-")
+}
+
+#[derive(Component)]
+struct Root;
+
+fn spawn(
+    mut commands: Commands,
+    camera: Single<Entity, With<OverlayCamera>>,
+) {
+    commands.spawn((
+        Root,
+        UiTargetCamera(*camera),
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(0.0),
+            right: Val::Px(0.0),
+            bottom: Val::Px(0.0),
+            padding: UiRect::all(Val::Px(8.0)),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.85)),
+        GlobalZIndex(i32::MAX),
+        Visibility::Hidden,
+        Pickable::IGNORE,
+        Text::default(),
+        TextFont {
+            font_size: 16.0,
+            ..default()
+        },
+    ));
+}
+
+/// Types into the console while it is open, hiding the keys from the game meanwhile.
+fn input(
+    mut console: ResMut<Console>,
+    mut keyboard: MessageReader<KeyboardInput>,
+    mut keys: ResMut<ButtonInput<KeyCode>>,
+) {
+    let was_open = console.open;
+
+    for input in keyboard.read() {
+        if input.state != ButtonState::Pressed {
+            continue;
+        }
+
+        if input.key_code == KeyCode::Backquote {
+            console.open = !console.open;
+            continue;
+        }
+
+        if !console.open {
+            continue;
+        }
+
+        match &input.logical_key {
+            Key::Enter => {
+                let line = std::mem::take(&mut console.input);
+                console.entered.push(line);
+            },
+            Key::Backspace => {
+                console.input.pop();
+            },
+            Key::Escape => console.open = false,
+            _ => {
+                if let Some(text) = &input.text {
+                    console
+                        .input
+                        .extend(text.chars().filter(|char| !char.is_control()));
+                }
+            },
+        }
+    }
+
+    if was_open || console.open {
+        keys.reset_all();
+    }
+}
+
+fn run(world: &mut World) {
+    let entered = std::mem::take(&mut world.resource_mut::<Console>().entered);
+
+    for line in entered {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        world.resource_mut::<Console>().print(format!("> {line}"));
+
+        let output = match Command::parse(&line) {
+            Ok(command) => command.run(world),
+            Err(message) => message,
+        };
+
+        let mut console = world.resource_mut::<Console>();
+
+        for line in output.lines() {
+            console.print(line.to_string());
+        }
+    }
+}
+
+/// Presses the tiles of [Console::presses] like the bot does, one per frame.
+fn press(
+    mut commands: Commands,
+    mut console: ResMut<Console>,
+    tiles: Query<&players::Player, InPlay>,
+    mut selected_tile: ResMut<SelectedTile>,
+    flip_back: Res<memory::FlipBack>,
+    mut frame_budget: ResMut<FrameBudget>,
+) {
+    if console.presses.is_empty() && console.pending.is_none() {
+        return;
+    }
+
+    frame_budget.request("console");
+
+    if let Some(entity) = console.pending.take() {
+        if tiles.contains(entity) {
+            bot::press(&mut commands, entity);
+        }
+        return;
+    }
+
+    let Some((a, b)) = console.presses.pop_front() else {
+        return;
+    };
+
+    let Ok(&player) = tiles.get(a) else {
+        return;
+    };
+
+    if flip_back.pending(player) {
+        console.presses.push_front((a, b));
+        return;
+    }
+
+    // Whatever was selected by hand would be paired with the first press.
+    selected_tile[player.0] = None;
+    bot::press(&mut commands, a);
+    console.pending = Some(b);
+}
+
+fn show(
+    console: Res<Console>,
+    root: Single<(&mut Text, &mut Visibility), With<Root>>,
+) {
+    if !console.is_changed() {
+        return;
+    }
+
+    let (mut text, mut visibility) = root.into_inner();
+
+    *visibility = if console.open {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+
+    let output = console
+        .output
+        .iter()
+        .cloned()
+        .collect::<Vec<_>>()
+        .join("\n");
+    text.0 = format!("{output}\n> {}_", console.input);
+}
@@ -0,0 +1,110 @@
+use crate::plugin::{
+    locale,
+    scene::main_menu::settings::{AppExt, Setting},
+};
+use bevy::{platform::collections::HashSet, prelude::*};
+
+use super::{
+    BoardUpdated, InPlay,
+    board::{Board, BoardIndex},
+    players, rules, tile,
+};
+
+pub struct Plugin;
+
+impl bevy::prelude::Plugin for Plugin {
+    fn build(
+        &self,
+        app: &mut App,
+    ) {
+        app.init_resource::<FreeTiles>()
+            .init_resource::<Indicator>()
+            .add_setting(Setting {
+                label: "settings.free_tiles",
+                value: |world| {
+                    if **world.resource::<Indicator>() {
+                        locale::translate(world, "settings.on")
+                    } else {
+                        locale::translate(world, "settings.off")
+                    }
+                },
+                adjust: |world, _| {
+                    let mut indicator = world.resource_mut::<Indicator>();
+                    **indicator = !**indicator;
+                },
+            });
+    }
+}
+
+/// Tiles which can currently be removed according to the active [rules::RuleSet]. Recomputed by
+/// [update] on every [BoardUpdated].
+#[derive(Resource, Default, Debug)]
+pub struct FreeTiles(HashSet<Entity>);
+
+impl FreeTiles {
+    pub fn contains(
+        &self,
+        entity: Entity,
+    ) -> bool {
+        self.0.contains(&entity)
+    }
+}
+
+/// Whether blocked tiles are dimmed and free ones brightened.
+#[derive(Resource, Default, Deref, DerefMut)]
+pub struct Indicator(bool);
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+/// A tile is free if no tile lies on top of it and at least one of its sides is open. Any number
+/// of neighbours on the same side (e.g. two half-offset tiles) only block that side. `board` are
+/// the positions of all tiles still in play.
+///
+/// This is the classic definition of freeness, which the board generator relies on. Input, help and
+/// move counting go through [rules::RuleSet::is_free], which may only ever be more lenient.
+pub fn is_free(
+    position: &tile::Position,
+    board: &Board,
+) -> bool {
+    board.above(position).next().is_none() && !is_enclosed(position, board)
+}
+
+/// Whether both sides of `position` have a neighbour.
+pub fn is_enclosed(
+    position: &tile::Position,
+    board: &Board,
+) -> bool {
+    board.neighbours(position, Side::Left).next().is_some()
+        && board.neighbours(position, Side::Right).next().is_some()
+}
+
+pub fn update(
+    mut board_updated: MessageReader<BoardUpdated>,
+    tiles: Query<(Entity, &tile::Position, &players::Player), InPlay>,
+    mut free_tiles: ResMut<FreeTiles>,
+    rules: Res<rules::Rules>,
+    index: Res<BoardIndex>,
+) {
+    if board_updated.read().count() == 0 {
+        return;
+    }
+
+    free_tiles.0 = tiles
+        .iter()
+        .filter(|(_, position, player)| rules.get().is_free(position, index.get(**player)))
+        .map(|(entity, ..)| entity)
+        .collect();
+}
+
+pub fn indicator_keyboard(
+    key: Res<ButtonInput<KeyCode>>,
+    mut indicator: ResMut<Indicator>,
+) {
+    if key.just_pressed(KeyCode::KeyB) {
+        **indicator = !**indicator;
+    }
+}
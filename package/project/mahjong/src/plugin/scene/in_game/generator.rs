@@ -0,0 +1,413 @@
+//! Deals boards which can always be cleared, by building them in reverse: pairs are placed where
+//! they would be free once every pair placed after them has been removed again.
+
+use bevy::prelude::*;
+use rand::{SeedableRng, rngs::StdRng, seq::SliceRandom};
+
+use super::tile;
+
+const TGS: u32 = tile::PositionGenerator::<tile::Turtle>::TILE_GRID_SIZE as u32;
+
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+enum Cell {
+    #[default]
+    Empty,
+    Available,
+    Occupied,
+}
+
+/// Spatial index of a layout by layer, row and column, holding the state of the tile whose
+/// [tile::Position] starts at a cell. Finding the tiles around a position only looks at the few cells
+/// next to it, instead of going through the whole board.
+#[derive(Clone)]
+struct Grid {
+    /// Columns, rows and layers. Each layer has a margin of [TGS] cells on all sides, so that the
+    /// cells around the outermost tiles exist as well.
+    size: UVec3,
+    cells: Vec<Cell>,
+    /// Occupied tiles per layer and row.
+    rows: Vec<u32>,
+}
+
+impl Grid {
+    /// # Panics
+    ///
+    /// If two positions overlap on the same layer.
+    fn new(positions: &[tile::Position]) -> Self {
+        let max = positions
+            .iter()
+            .fold(UVec3::ZERO, |max, position| max.max(position.0));
+        let size = UVec3::new(max.x + 1 + 2 * TGS, max.y + 1 + 2 * TGS, max.z + 1);
+
+        let mut grid = Grid {
+            size,
+            cells: vec![Cell::Empty; (size.x * size.y * size.z) as usize],
+            rows: vec![0; (size.y * size.z) as usize],
+        };
+
+        for position in positions {
+            let cell = Self::cell(position);
+
+            if grid.overlapping(cell, cell.z..cell.z + 1, Cell::Available) {
+                panic!("Invalid/bad tile positioning!")
+            }
+
+            grid.set(position, Cell::Available);
+        }
+
+        grid
+    }
+
+    /// Cell of the tile at `position`, offset by the margin.
+    fn cell(position: &tile::Position) -> UVec3 {
+        position.0 + UVec3::new(TGS, TGS, 0)
+    }
+
+    fn row(
+        &self,
+        y: u32,
+        z: u32,
+    ) -> usize {
+        (z * self.size.y + y) as usize
+    }
+
+    fn get(
+        &self,
+        x: u32,
+        y: u32,
+        z: u32,
+    ) -> Cell {
+        self.cells[self.row(y, z) * self.size.x as usize + x as usize]
+    }
+
+    fn set(
+        &mut self,
+        position: &tile::Position,
+        cell: Cell,
+    ) {
+        let at = Self::cell(position);
+        let row = self.row(at.y, at.z);
+        let index = row * self.size.x as usize + at.x as usize;
+
+        if self.cells[index] == Cell::Occupied {
+            self.rows[row] -= 1;
+        }
+
+        if cell == Cell::Occupied {
+            self.rows[row] += 1;
+        }
+
+        self.cells[index] = cell;
+    }
+
+    /// Cells along one axis at which a tile overlaps a tile at cell `v`, e.g. the rows of tiles which
+    /// share (part of) a row with it.
+    fn around(v: u32) -> std::ops::Range<u32> {
+        v + 1 - TGS..v + TGS
+    }
+
+    /// Returns true if a tile in state `cell` on one of `layers` overlaps the tile at `at`.
+    fn overlapping(
+        &self,
+        at: UVec3,
+        layers: std::ops::Range<u32>,
+        cell: Cell,
+    ) -> bool {
+        layers.into_iter().any(|z| {
+            Self::around(at.y).any(|y| Self::around(at.x).any(|x| self.get(x, y, z) == cell))
+        })
+    }
+
+    /// Returns true if an occupied tile at column `x` is a neighbour of the tile at `at`, and shares
+    /// a row with row `y` if given.
+    fn neighbour(
+        &self,
+        at: UVec3,
+        x: u32,
+        y: Option<u32>,
+    ) -> bool {
+        Self::around(at.y)
+            .filter(|row| y.is_none_or(|y| Self::around(y).contains(row)))
+            .any(|row| self.get(x, row, at.z) == Cell::Occupied)
+    }
+
+    /// Same as [super::free::is_free] with the occupied tiles as the board.
+    fn is_free(
+        &self,
+        position: &tile::Position,
+    ) -> bool {
+        let at = Self::cell(position);
+
+        if self.overlapping(at, at.z + 1..self.size.z, Cell::Occupied) {
+            return false;
+        }
+
+        !(self.neighbour(at, at.x - TGS, None) && self.neighbour(at, at.x + TGS, None))
+    }
+
+    /// Returns true if placing a tile at `a` may change whether the tile at `b` is valid or free:
+    /// `b` shares a row with `a` on its layer, or lies (partly) above or below it.
+    fn affects(
+        a: &tile::Position,
+        b: &tile::Position,
+    ) -> bool {
+        let overlapping = |a: u32, b: u32| a.abs_diff(b) < TGS;
+
+        overlapping(a.y, b.y) && (a.z == b.z || overlapping(a.x, b.x))
+    }
+
+    /// Available tiles which placing a tile at `position` may make valid or invalid, see
+    /// [Grid::affects].
+    fn affected(
+        &self,
+        position: &tile::Position,
+    ) -> impl Iterator<Item = tile::Position> {
+        let at = Self::cell(position);
+
+        (0..self.size.z).flat_map(move |z| {
+            let columns = if z == at.z {
+                0..self.size.x
+            } else {
+                Self::around(at.x)
+            };
+
+            Self::around(at.y).flat_map(move |y| {
+                columns
+                    .clone()
+                    .filter(move |x| self.get(*x, y, z) == Cell::Available)
+                    .map(move |x| tile::Position(UVec3::new(x, y, z) - UVec3::new(TGS, TGS, 0)))
+            })
+        })
+    }
+
+    /// Returns true if the available tile at `position` is a candidate to place next.
+    fn is_valid(
+        &self,
+        position: &tile::Position,
+    ) -> bool {
+        let at = Self::cell(position);
+
+        if self.overlapping(at, 0..at.z, Cell::Available) || !self.is_free(position) {
+            return false;
+        }
+
+        // Rows are grown outwards from their first tile. Starting a second run in an occupied row
+        // would leave a gap, which can only be filled by a tile blocked on both sides. A half-offset
+        // tile spans two rows, and has to continue the run of each.
+        Self::around(at.y)
+            .filter(|y| self.rows[self.row(*y, at.z)] > 0)
+            .all(|y| {
+                self.neighbour(at, at.x - TGS, Some(y)) || self.neighbour(at, at.x + TGS, Some(y))
+            })
+    }
+}
+
+/// Returns a [Vec] with (position, variant) tuples along with the rng seed ([u64]) to create them.
+/// When no pair can be placed, the deal is started over with the next seed.
+pub fn generate_solvable_board(
+    positions: Vec<tile::Position>,
+    seed: Option<u64>,
+) -> (Vec<(tile::Position, tile::Variant)>, u64) {
+    if !positions.len().is_multiple_of(2) {
+        panic!();
+    }
+
+    let grid = Grid::new(&positions);
+    let mut seed = seed.unwrap_or(rand::random());
+
+    loop {
+        if let Some(result) = generate(positions.clone(), grid.clone(), seed) {
+            return (result, seed);
+        }
+
+        seed = seed.wrapping_add(1);
+    }
+}
+
+fn generate(
+    positions: Vec<tile::Position>,
+    mut grid: Grid,
+    seed: u64,
+) -> Option<Vec<(tile::Position, tile::Variant)>> {
+    let mut result: Vec<(tile::Position, tile::Variant)> = Vec::new();
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    // Generate [tile::Variant] pairs
+    let mut available_tile_variants = tile::Variant::deal(positions.len());
+    available_tile_variants.shuffle(&mut rng);
+
+    // Tiles which are valid to place next. Placing a pair only changes this around the pair, see
+    // [Grid::affected].
+    let mut candidates: Vec<tile::Position> = positions
+        .into_iter()
+        .filter(|position| grid.is_valid(position))
+        .collect();
+
+    for (v0, v1) in available_tile_variants {
+        let mut valid = candidates.clone();
+        valid.shuffle(&mut rng);
+
+        let mut chosen_pair = None;
+
+        // Find a pair that remains valid after first placement
+        for &a in &valid {
+            grid.set(&a, Cell::Occupied);
+
+            // Tiles away from the first one stay valid, and can not block it.
+            chosen_pair = valid
+                .iter()
+                .filter(|b| **b != a)
+                .find(|b| {
+                    if !Grid::affects(&a, b) {
+                        return true;
+                    }
+
+                    if !grid.is_valid(b) {
+                        return false;
+                    }
+
+                    // Both tiles of a pair are removed together, so placing b must not block a.
+                    grid.set(b, Cell::Occupied);
+                    let free = grid.is_free(&a);
+                    grid.set(b, Cell::Available);
+
+                    free
+                })
+                .map(|b| (a, *b));
+
+            grid.set(&a, Cell::Available);
+
+            if chosen_pair.is_some() {
+                break;
+            }
+        }
+
+        let (a, b) = chosen_pair?;
+
+        for (position, variant) in [(a, v0), (b, v1)] {
+            grid.set(&position, Cell::Occupied);
+            result.push((position, variant));
+        }
+
+        let mut affected: Vec<tile::Position> =
+            grid.affected(&a).chain(grid.affected(&b)).collect();
+        affected.sort_by_key(|position| (position.z, position.y, position.x));
+        affected.dedup();
+
+        candidates
+            .retain(|position| *position != a && *position != b && !affected.contains(position));
+        candidates.extend(
+            affected
+                .into_iter()
+                .filter(|position| grid.is_valid(position)),
+        );
+    }
+
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::UVec2;
+    use rand::Rng;
+
+    use super::{super::board::Board, super::free, super::rules, *};
+
+    const SEEDS: u64 = 16;
+
+    fn turtle() -> Vec<tile::Position> {
+        tile::PositionGenerator::<tile::Turtle>::new(UVec2::splat(TGS)).collect()
+    }
+
+    /// Two turtles, one above the other.
+    fn twin_turtle() -> Vec<tile::Position> {
+        let height = turtle().iter().map(|position| position.y).max().unwrap() + TGS;
+        let twin = turtle()
+            .into_iter()
+            .map(|position| tile::Position(position.0 + UVec3::Y * height));
+
+        turtle().into_iter().chain(twin).collect()
+    }
+
+    /// Layers of rectangles, each one tile smaller on every side than the one below, and half a tile
+    /// off to the right and down.
+    fn pyramid() -> Vec<tile::Position> {
+        (0..4)
+            .flat_map(|z| {
+                let offset = z * (TGS + TGS / 2);
+                (0..8 - 2 * z).flat_map(move |row| {
+                    (0..12 - 2 * z).map(move |column| {
+                        tile::Position(UVec3::new(offset + column * TGS, offset + row * TGS, z))
+                    })
+                })
+            })
+            .collect()
+    }
+
+    /// Checks that every way of telling whether a tile of `board` is free agrees, `board` being
+    /// some of the tiles of `layout`.
+    fn assert_agree(
+        layout: &[tile::Position],
+        board: &Board,
+    ) {
+        let mut grid = Grid::new(layout);
+
+        for position in board.iter() {
+            grid.set(position, Cell::Occupied);
+        }
+
+        for position in board.iter() {
+            let free = free::is_free(position, board);
+
+            assert_eq!(grid.is_free(position), free, "Grid at {position:?}");
+            assert_eq!(
+                rules::RULE_SETS[0].is_free(position, board),
+                free,
+                "Classic rules at {position:?}"
+            );
+
+            for rules in rules::RULE_SETS {
+                assert!(
+                    !free || rules.is_free(position, board),
+                    "{} is stricter at {position:?}",
+                    rules.key()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn freeness_agrees() {
+        for layout in [turtle(), twin_turtle(), pyramid()] {
+            for seed in 0..SEEDS {
+                let (deal, _) = generate_solvable_board(layout.clone(), Some(seed));
+                let mut board: Board = deal.iter().map(|(position, _)| *position).collect();
+
+                assert_eq!(board.len(), layout.len());
+
+                // The pair placed last is the first one to be removed.
+                for pair in deal.chunks(2).rev() {
+                    assert_agree(&layout, &board);
+                    assert!(pair[0].1.matches(&pair[1].1));
+
+                    for (position, _) in pair {
+                        assert!(free::is_free(position, &board), "Dealt {position:?}");
+                    }
+
+                    for (position, _) in pair {
+                        board.remove(position);
+                    }
+                }
+
+                // Boards which generation would never leave behind, e.g. with gaps in rows.
+                let mut rng = StdRng::seed_from_u64(seed);
+                let board: Board = layout
+                    .iter()
+                    .copied()
+                    .filter(|_| rng.random_bool(0.5))
+                    .collect();
+                assert_agree(&layout, &board);
+            }
+        }
+    }
+}
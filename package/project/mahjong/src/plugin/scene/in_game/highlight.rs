@@ -0,0 +1,363 @@
+use crate::plugin::{
+    locale,
+    scene::main_menu::settings::{AppExt, Setting},
+};
+use bevy::{a11y::AccessibilityNode, ecs::system::SystemParam, prelude::*, sprite::Text2dShadow};
+
+use super::{HelpEnabled, SelectedTile, board, free, marker, players, rules, theme, tile};
+
+pub struct Plugin;
+
+impl bevy::prelude::Plugin for Plugin {
+    fn build(
+        &self,
+        app: &mut App,
+    ) {
+        app.init_resource::<Palette>().add_setting(Setting {
+            label: "settings.palette",
+            value: |world| locale::translate(world, world.resource::<Palette>().key()),
+            adjust: |world, delta| {
+                let mut palette = world.resource_mut::<Palette>();
+                *palette = palette.step(delta);
+            },
+        });
+    }
+}
+
+/// Colours used to highlight tiles. Every highlight is also drawn as an outline and a glyph, so
+/// telling them apart never relies on colour alone.
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Palette {
+    #[default]
+    Default,
+    Deuteranopia,
+    Protanopia,
+    Tritanopia,
+    HighContrast,
+}
+
+impl Palette {
+    pub const ALL: [Palette; 5] = [
+        Palette::Default,
+        Palette::Deuteranopia,
+        Palette::Protanopia,
+        Palette::Tritanopia,
+        Palette::HighContrast,
+    ];
+
+    /// Message key of the name.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Palette::Default => "palette.default",
+            Palette::Deuteranopia => "palette.deuteranopia",
+            Palette::Protanopia => "palette.protanopia",
+            Palette::Tritanopia => "palette.tritanopia",
+            Palette::HighContrast => "palette.high_contrast",
+        }
+    }
+
+    fn step(
+        &self,
+        delta: i32,
+    ) -> Self {
+        let index = Self::ALL
+            .iter()
+            .position(|palette| palette == self)
+            .unwrap() as i32;
+        Self::ALL[(index + delta).rem_euclid(Self::ALL.len() as i32) as usize]
+    }
+
+    pub fn color(
+        &self,
+        highlight: Highlight,
+    ) -> Color {
+        use Highlight::*;
+
+        match (self, highlight) {
+            (Palette::Default, Selected) => Color::hsl(0.5, 1.0, 1.5),
+            (Palette::Default, Hint) => Color::hsl(120.0, 1.0, 0.5),
+            // Blue against orange stays distinct without a working red-green axis.
+            (Palette::Deuteranopia, Selected) => Color::srgb(0.35, 0.6, 1.0),
+            (Palette::Deuteranopia, Hint) => Color::srgb(1.0, 0.6, 0.0),
+            (Palette::Protanopia, Selected) => Color::srgb(0.3, 0.55, 1.0),
+            (Palette::Protanopia, Hint) => Color::srgb(1.0, 0.9, 0.2),
+            // Red against cyan stays distinct without a working blue-yellow axis.
+            (Palette::Tritanopia, Selected) => Color::srgb(1.0, 0.3, 0.4),
+            (Palette::Tritanopia, Hint) => Color::srgb(0.2, 0.9, 0.9),
+            (Palette::HighContrast, Selected) => Color::srgb(1.0, 1.0, 0.0),
+            (Palette::HighContrast, Hint) => Color::srgb(1.0, 0.0, 1.0),
+        }
+    }
+}
+
+/// Highlight currently shown on a tile. Derived from [SelectedTile], [HelpEnabled] and
+/// [rules::RuleSet::hint] by [apply].
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Highlight {
+    Selected,
+    /// Could be removed along with the selected tile, shown while help is enabled.
+    Hint,
+}
+
+impl Highlight {
+    fn glyph(&self) -> &'static str {
+        match self {
+            Highlight::Selected => "*",
+            Highlight::Hint => "?",
+        }
+    }
+}
+
+/// How a tile without a [Highlight] is shown while the [free::Indicator] is enabled.
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Shade {
+    /// Brightened, it can be removed.
+    Free,
+    /// Dimmed, it is blocked.
+    Blocked,
+}
+
+/// Outline and glyph drawn on top of a highlighted tile.
+#[derive(Component)]
+pub struct Marker;
+
+/// Shade over a blocked tile while the [free::Indicator] is enabled.
+#[derive(Component)]
+pub struct Dim;
+
+/// Thickness of the outline, as a fraction of the tile width.
+const OUTLINE: f32 = 0.06;
+
+fn spawn_marker(
+    parent: &mut ChildSpawnerCommands,
+    highlight: Highlight,
+    color: Color,
+    geometry: &tile::Geometry,
+) {
+    let size = geometry.size;
+    let thickness = size.x * OUTLINE;
+    let offset = geometry.face_offset().with_z(2.0);
+
+    parent
+        .spawn((
+            Marker,
+            Transform::from_translation(offset),
+            Visibility::Inherited,
+        ))
+        .with_children(|parent| {
+            let bars = [
+                (
+                    Vec2::new(0.0, (size.y - thickness) / 2.0),
+                    Vec2::new(size.x, thickness),
+                ),
+                (
+                    Vec2::new(0.0, -(size.y - thickness) / 2.0),
+                    Vec2::new(size.x, thickness),
+                ),
+                (
+                    Vec2::new(-(size.x - thickness) / 2.0, 0.0),
+                    Vec2::new(thickness, size.y),
+                ),
+                (
+                    Vec2::new((size.x - thickness) / 2.0, 0.0),
+                    Vec2::new(thickness, size.y),
+                ),
+            ];
+
+            for (translation, bar) in bars {
+                parent.spawn((
+                    Sprite::from_color(color, bar),
+                    Transform::from_translation(translation.extend(0.0)),
+                ));
+            }
+
+            parent.spawn((
+                Text2d::new(highlight.glyph()),
+                TextFont {
+                    font_size: size.y * 0.3,
+                    ..default()
+                },
+                TextColor(color),
+                Text2dShadow {
+                    offset: Vec2::new(2.0, -2.0),
+                    color: Color::BLACK,
+                },
+                Transform::from_translation(Vec3::new(
+                    size.x / 2.0 - thickness * 2.5,
+                    size.y / 2.0 - thickness * 3.0,
+                    1.0,
+                )),
+            ));
+        });
+}
+
+/// Tile with what [apply] reads and changes of it.
+type HighlightedTile = (
+    Entity,
+    &'static tile::Variant,
+    &'static tile::Position,
+    &'static mut Sprite,
+    Option<&'static Highlight>,
+    Option<&'static Shade>,
+    Option<&'static mut AccessibilityNode>,
+    Has<marker::Hidden>,
+    &'static players::Player,
+);
+/// Children drawn by [apply] on top of a tile.
+type Overlays = Or<(With<Marker>, With<Dim>)>;
+
+/// Everything deciding how tiles are highlighted, see [apply].
+#[derive(SystemParam)]
+pub struct Highlighting<'w, 's> {
+    selected_tile: Res<'w, SelectedTile>,
+    help_enabled: Res<'w, HelpEnabled>,
+    palette: Res<'w, Palette>,
+    free_tiles: Res<'w, free::FreeTiles>,
+    indicator: Res<'w, free::Indicator>,
+    rules: Res<'w, rules::Rules>,
+    index: Res<'w, board::BoardIndex>,
+    theme: theme::Active<'w>,
+    theme_events: MessageReader<'w, 's, AssetEvent<theme::Theme>>,
+}
+
+/// Single place where tiles get tinted and marked. Runs whenever the selection, help, palette,
+/// theme or [free::FreeTiles] change, but only redraws the tiles which look different since, unless
+/// the palette or theme did.
+pub fn apply(
+    mut commands: Commands,
+    mut tiles: Query<HighlightedTile, With<tile::Marker<0>>>,
+    markers: Query<(Entity, &ChildOf), Overlays>,
+    projection: Query<&Projection, With<Camera>>,
+    highlighting: Highlighting,
+    mut applied: Local<Option<AssetId<theme::Theme>>>,
+) {
+    let Highlighting {
+        selected_tile,
+        help_enabled,
+        palette,
+        free_tiles,
+        indicator,
+        rules,
+        index,
+        theme,
+        mut theme_events,
+    } = highlighting;
+
+    let theme_changed = theme_events
+        .read()
+        .any(|event| event.is_modified(theme.id()))
+        || *applied != Some(theme.id());
+
+    let refresh_all = theme_changed || palette.is_changed();
+
+    if !theme.is_loaded()
+        || !(refresh_all
+            || selected_tile.is_changed()
+            || help_enabled.is_changed()
+            || free_tiles.is_changed()
+            || indicator.is_changed())
+    {
+        return;
+    }
+    *applied = Some(theme.id());
+
+    let Some(Projection::Orthographic(projection)) = projection.iter().next() else {
+        panic!();
+    };
+
+    let theme = theme.get();
+    let geometry = tile::Geometry::new(projection, &theme.tile);
+    let selected: [Option<(tile::Variant, tile::Position)>; players::PLAYERS] =
+        std::array::from_fn(|player| {
+            selected_tile[player]
+                .and_then(|entity| tiles.get(entity).ok())
+                .map(|(_, variant, position, ..)| (*variant, *position))
+        });
+    for (
+        entity,
+        variant,
+        position,
+        mut sprite,
+        current,
+        current_shade,
+        accessibility,
+        hidden,
+        player,
+    ) in &mut tiles
+    {
+        let highlight = if selected_tile[player.0] == Some(entity) {
+            Some(Highlight::Selected)
+        } else if **help_enabled
+            && !hidden
+            && selected[player.0].is_some_and(|(selected_variant, selected_position)| {
+                rules.get().hint(
+                    (&selected_variant, &selected_position),
+                    (variant, position),
+                    index.get(*player),
+                )
+            })
+        {
+            Some(Highlight::Hint)
+        } else {
+            None
+        };
+
+        let shade = (highlight.is_none() && **indicator && !hidden).then(|| {
+            if free_tiles.contains(entity) {
+                Shade::Free
+            } else {
+                Shade::Blocked
+            }
+        });
+
+        if let Some(mut accessibility) = accessibility {
+            accessibility.set_selected(highlight == Some(Highlight::Selected));
+        }
+
+        if highlight == current.copied() && shade == current_shade.copied() && !refresh_all {
+            continue;
+        }
+
+        for (marker, _) in markers
+            .iter()
+            .filter(|(_, child_of)| child_of.parent() == entity)
+        {
+            commands.entity(marker).despawn();
+        }
+
+        match (highlight, shade) {
+            (Some(highlight), _) => {
+                let color = palette.color(highlight);
+                sprite.color = color;
+                commands
+                    .entity(entity)
+                    .insert(highlight)
+                    .remove::<Shade>()
+                    .with_children(|parent| spawn_marker(parent, highlight, color, &geometry));
+            },
+            (None, Some(Shade::Free)) => {
+                sprite.color = theme.tile.color.lighter(0.1);
+                commands
+                    .entity(entity)
+                    .remove::<Highlight>()
+                    .insert(Shade::Free);
+            },
+            (None, Some(Shade::Blocked)) => {
+                sprite.color = theme.tile.color;
+                commands
+                    .entity(entity)
+                    .remove::<Highlight>()
+                    .insert(Shade::Blocked)
+                    .with_child((
+                        Dim,
+                        Sprite::from_color(Color::srgba(0.0, 0.0, 0.0, 0.45), geometry.size_full),
+                        Transform::from_translation(Vec3::default().with_z(3.0)),
+                    ));
+            },
+            (None, None) => {
+                sprite.color = theme.tile.color;
+                commands.entity(entity).remove::<(Highlight, Shade)>();
+            },
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use crate::plugin::default::OverlayCamera;
+use bevy::prelude::*;
+
+use super::{InGame, tile};
+
+/// Window height (logical pixels) the HUD is laid out for. [UiScale] is derived from it, so the
+/// HUD follows the window size the same way the world does through `ScalingMode::FixedVertical`.
+pub const REFERENCE_HEIGHT: f32 = 1080.0;
+
+/// User adjustable factor applied on top of the window derived [UiScale].
+#[derive(Resource, Deref, DerefMut, Clone, Copy, Debug)]
+pub struct Scale(pub f32);
+
+impl Default for Scale {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+/// Screen corner which a HUD element is stacked into.
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Anchor {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl Anchor {
+    pub const ALL: [Anchor; 4] = [
+        Anchor::TopLeft,
+        Anchor::TopRight,
+        Anchor::BottomLeft,
+        Anchor::BottomRight,
+    ];
+
+    fn is_left(&self) -> bool {
+        matches!(self, Anchor::TopLeft | Anchor::BottomLeft)
+    }
+
+    fn is_bottom(&self) -> bool {
+        matches!(self, Anchor::BottomLeft | Anchor::BottomRight)
+    }
+
+    /// Absolutely positioned column hugging the corner. Children are stacked away from the corner,
+    /// i.e. the first child spawned is the one closest to it.
+    pub fn node(&self) -> Node {
+        let edge = |condition: bool| if condition { Val::Px(0.0) } else { Val::Auto };
+
+        Node {
+            position_type: PositionType::Absolute,
+            left: edge(self.is_left()),
+            right: edge(!self.is_left()),
+            top: edge(!self.is_bottom()),
+            bottom: edge(self.is_bottom()),
+            flex_direction: if self.is_bottom() {
+                FlexDirection::ColumnReverse
+            } else {
+                FlexDirection::Column
+            },
+            align_items: if self.is_left() {
+                AlignItems::FlexStart
+            } else {
+                AlignItems::FlexEnd
+            },
+            ..default()
+        }
+    }
+}
+
+/// Size of a single HUD element (button, info slab) at [REFERENCE_HEIGHT].
+pub fn element_size() -> Vec2 {
+    let height = REFERENCE_HEIGHT / tile::PositionGenerator::<tile::Turtle>::ROWS as f32;
+    Vec2::new(height / 0.7, height)
+}
+
+pub fn font(font_size: f32) -> impl Bundle + Clone {
+    (
+        TextFont {
+            font_size,
+            ..default()
+        },
+        TextShadow {
+            offset: Vec2 { x: 3.0, y: 3.0 },
+            color: Color::srgba(0.0, 0.0, 0.0, 0.95),
+        },
+        TextColor(Color::srgb_u8(239, 191, 4)),
+    )
+}
+
+/// Spawns one (empty) container per [Anchor], rendered by the [OverlayCamera].
+pub fn spawn_anchors(
+    mut commands: Commands,
+    camera: Single<Entity, With<OverlayCamera>>,
+) {
+    for anchor in Anchor::ALL {
+        commands.spawn((
+            DespawnOnExit(InGame::Running),
+            anchor,
+            anchor.node(),
+            UiTargetCamera(*camera),
+            Pickable::IGNORE,
+        ));
+    }
+}
+
+pub fn anchor_entity(
+    anchors: &Query<(Entity, &Anchor)>,
+    anchor: Anchor,
+) -> Entity {
+    anchors
+        .iter()
+        .find_map(|(entity, other)| (*other == anchor).then_some(entity))
+        .expect("HUD anchors not spawned!")
+}
+
+pub fn scale(
+    window: Single<&Window>,
+    scale: Res<Scale>,
+    mut ui_scale: ResMut<UiScale>,
+) {
+    let value = window.height() / REFERENCE_HEIGHT * **scale;
+
+    if ui_scale.0 != value {
+        ui_scale.0 = value;
+    }
+}
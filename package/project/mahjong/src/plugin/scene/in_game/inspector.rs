@@ -0,0 +1,154 @@
+//! Inspector panels of the game in progress, next to the world inspector of the `inspector`
+//! feature (see [crate::plugin::external]). Each can also change what it shows, e.g. force a seed or
+//! remove a tile, to get to a state worth debugging without playing there.
+
+use crate::plugin::platform::{Platform, PlatformTrait};
+use bevy::prelude::*;
+use bevy_inspector_egui::bevy_egui::{EguiContexts, EguiPrimaryContextPass, egui};
+
+use super::{
+    BoardUpdated, History, HistoryItem, InGame, Seed, SelectedTile, TilePositionVariantPairs,
+    marker, players, tile,
+};
+
+pub struct Plugin;
+
+impl bevy::prelude::Plugin for Plugin {
+    fn build(
+        &self,
+        app: &mut App,
+    ) {
+        app.add_systems(
+            EguiPrimaryContextPass,
+            (seed, selected_tile, tile_position_variant_pairs, history)
+                .run_if(in_state(InGame::Running)),
+        );
+    }
+}
+
+/// Deals the board of the seed typed in, as a new game would.
+fn seed(
+    mut contexts: EguiContexts,
+    seed: Res<Seed>,
+    platform: Res<Platform>,
+    mut next_state: ResMut<NextState<InGame>>,
+    mut forced: Local<String>,
+) -> Result {
+    egui::Window::new("Seed").show(contexts.ctx_mut()?, |ui| {
+        ui.label(seed.map_or("-".to_string(), |seed| seed.to_string()));
+
+        ui.horizontal(|ui| {
+            ui.text_edit_singleline(&mut *forced);
+
+            let parsed = forced.trim().parse::<u64>();
+
+            if ui
+                .add_enabled(parsed.is_ok(), egui::Button::new("Deal"))
+                .clicked()
+                && let Ok(forced) = parsed
+            {
+                info!("Dealing seed {forced}");
+                platform.rng_seed_set(forced);
+                next_state.set(InGame::Root);
+            }
+        });
+    });
+
+    Ok(())
+}
+
+fn selected_tile(
+    mut contexts: EguiContexts,
+    mut selected_tile: ResMut<SelectedTile>,
+    tiles: Query<(&tile::Position, &tile::Variant), With<tile::Marker<0>>>,
+) -> Result {
+    egui::Window::new("Selected tile").show(contexts.ctx_mut()?, |ui| {
+        for player in 0..players::PLAYERS {
+            ui.horizontal(|ui| {
+                let selected = selected_tile[player].and_then(|entity| {
+                    tiles
+                        .get(entity)
+                        .ok()
+                        .map(|(position, variant)| (entity, position, variant))
+                });
+
+                let Some((entity, position, variant)) = selected else {
+                    ui.label(format!("Player {player}: -"));
+                    return;
+                };
+
+                ui.label(format!(
+                    "Player {player}: {entity} at {} ({})",
+                    **position, **variant
+                ));
+
+                if ui.button("Deselect").clicked() {
+                    selected_tile[player] = None;
+                }
+            });
+        }
+    });
+
+    Ok(())
+}
+
+/// Lists the deal, and removes tiles from every board, even if that leaves one unsolvable.
+fn tile_position_variant_pairs(
+    mut contexts: EguiContexts,
+    mut commands: Commands,
+    pairs: Res<TilePositionVariantPairs>,
+    tiles: Query<(Entity, &tile::Position), (With<tile::Marker<0>>, Without<marker::Hidden>)>,
+    mut board_updated: MessageWriter<BoardUpdated>,
+) -> Result {
+    egui::Window::new("Tiles").show(contexts.ctx_mut()?, |ui| {
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for (position, variant) in pairs.iter() {
+                let in_play: Vec<Entity> = tiles
+                    .iter()
+                    .filter(|(_, tile_position)| *tile_position == position)
+                    .map(|(entity, _)| entity)
+                    .collect();
+
+                ui.horizontal(|ui| {
+                    ui.label(format!("{} ({})", **position, **variant));
+
+                    if ui
+                        .add_enabled(!in_play.is_empty(), egui::Button::new("Remove"))
+                        .clicked()
+                    {
+                        for &entity in &in_play {
+                            commands
+                                .entity(entity)
+                                .insert((marker::Hidden, Visibility::Hidden));
+                        }
+
+                        board_updated.write(BoardUpdated);
+                    }
+                });
+            }
+        });
+    });
+
+    Ok(())
+}
+
+fn history(
+    mut contexts: EguiContexts,
+    mut history: ResMut<History>,
+) -> Result {
+    egui::Window::new("History").show(contexts.ctx_mut()?, |ui| {
+        for (name, items) in [("Undo", &history.undo), ("Redo", &history.redo)] {
+            ui.collapsing(format!("{name} ({})", items.len()), |ui| {
+                for HistoryItem::ValidPair(entity0, entity1) in items {
+                    ui.label(format!("Pair {entity0}, {entity1}"));
+                }
+            });
+        }
+
+        if ui.button("Clear").clicked() {
+            *history = History::default();
+        }
+    });
+
+    Ok(())
+}
@@ -0,0 +1,346 @@
+use crate::plugin::{audio::Sfx, frame_budget::FrameBudget, locale::Tr};
+use bevy::{a11y::AccessibilityNode, ecs::system::SystemParam, prelude::*};
+use rand::{SeedableRng, rngs::StdRng, seq::SliceRandom};
+use std::time::Duration;
+
+use super::{
+    BoardUpdated, History, HistoryItem, InGame, InPlay, PairRemoved, SelectedTile,
+    board::Board,
+    marker,
+    players::{self, Players},
+    rules::{Mode, RuleSet, Rules},
+    theme, tile,
+};
+
+pub struct Plugin;
+
+impl bevy::prelude::Plugin for Plugin {
+    fn build(
+        &self,
+        app: &mut App,
+    ) {
+        app.init_resource::<FlipBack>()
+            .init_resource::<Reveal>()
+            .add_observer(remove_pair)
+            .add_observer(restore_pair)
+            .add_systems(OnEnter(InGame::Root), |mut flip_back: ResMut<FlipBack>| {
+                *flip_back = FlipBack::default();
+            })
+            .add_systems(
+                Update,
+                (
+                    (flip_back, turn_unselected).run_if(in_state(InGame::Running)),
+                    show_faces,
+                )
+                    .run_if(in_state(InGame::Init).or(in_state(InGame::Running))),
+            );
+    }
+}
+
+/// Size of the board, in tiles.
+pub const COLUMNS: u32 = 8;
+pub const ROWS: u32 = 5;
+pub const TILES: usize = (COLUMNS * ROWS) as usize;
+
+/// How long a mismatched pair stays face up.
+const REVEAL: Duration = Duration::from_millis(1000);
+
+/// Memory: tiles lie face down, and any two matching tiles turned face up are removed.
+pub struct Memory;
+
+impl RuleSet for Memory {
+    fn key(&self) -> &'static str {
+        "mode.memory"
+    }
+
+    fn is_free(
+        &self,
+        _position: &tile::Position,
+        _board: &Board,
+    ) -> bool {
+        true
+    }
+
+    fn valid_removal(
+        &self,
+        a: (&tile::Variant, &tile::Position),
+        b: (&tile::Variant, &tile::Position),
+        _board: &Board,
+    ) -> bool {
+        a.0.matches(b.0)
+    }
+
+    /// Would give the faces away.
+    fn hint(
+        &self,
+        _selected: (&tile::Variant, &tile::Position),
+        _candidate: (&tile::Variant, &tile::Position),
+        _board: &Board,
+    ) -> bool {
+        false
+    }
+
+    fn timed(&self) -> bool {
+        true
+    }
+}
+
+/// Returns a [Vec] with (position, variant) tuples along with the rng seed ([u64]) to create them.
+/// Every deal can be cleared, so unlike the other modes there is nothing to retry.
+pub fn generate_board(seed: Option<u64>) -> (Vec<(tile::Position, tile::Variant)>, u64) {
+    let seed = seed.unwrap_or(rand::random());
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let mut variants: Vec<tile::Variant> = tile::Variant::deal(TILES)
+        .into_iter()
+        .flat_map(|(v0, v1)| [v0, v1])
+        .collect();
+    variants.shuffle(&mut rng);
+
+    // Centered on the turtle, which is 15 tiles wide and 8 tiles high.
+    let board = (0..ROWS)
+        .flat_map(|row| {
+            (0..COLUMNS).map(move |column| {
+                tile::Position(UVec3::new(15 - COLUMNS + 2 * column, 8 - ROWS + 2 * row, 0))
+            })
+        })
+        .zip(variants)
+        .collect();
+
+    (board, seed)
+}
+
+/// Tile showing its back rather than its face.
+#[derive(Component)]
+pub struct FaceDown;
+
+/// Whether the faces of face down tiles are shown anyway, for debugging.
+#[derive(Resource, Default, Deref, DerefMut)]
+pub struct Reveal(bool);
+
+/// Mismatched pair of each [players::Player] waiting to be turned face down again. No tile on that
+/// board can be turned meanwhile.
+#[derive(Resource, Default)]
+pub struct FlipBack([Option<([Entity; 2], Timer)>; players::PLAYERS]);
+
+impl FlipBack {
+    /// Whether the board of `player` waits for a mismatched pair to be turned face down.
+    pub fn pending(
+        &self,
+        player: players::Player,
+    ) -> bool {
+        self.0[player.0].is_some()
+    }
+}
+
+type TurnedTile = (
+    Entity,
+    &'static tile::Variant,
+    &'static tile::Position,
+    Has<FaceDown>,
+    &'static players::Player,
+);
+
+/// State of the game which turning a tile reads and changes.
+#[derive(SystemParam)]
+pub(super) struct Turn<'w> {
+    selected_tile: ResMut<'w, SelectedTile>,
+    history: ResMut<'w, History>,
+    flip_back: ResMut<'w, FlipBack>,
+    players: ResMut<'w, Players>,
+    next_state: ResMut<'w, NextState<InGame>>,
+}
+
+pub(super) fn tile_pressed(
+    on_press: On<Pointer<Press>>,
+    mut commands: Commands,
+    tiles: Query<TurnedTile, InPlay>,
+    turn: Turn,
+    mut board_updated: MessageWriter<BoardUpdated>,
+    mut pair_removed: MessageWriter<PairRemoved>,
+    mut sfx: MessageWriter<Sfx>,
+) {
+    let Turn {
+        mut selected_tile,
+        mut history,
+        mut flip_back,
+        mut players,
+        mut next_state,
+    } = turn;
+
+    let Ok((pressed_entity, pressed_variant, pressed_position, face_down, &player)) =
+        tiles.get(on_press.entity)
+    else {
+        return;
+    };
+
+    if flip_back.0[player.0].is_some() || !face_down {
+        return;
+    }
+
+    commands.entity(pressed_entity).remove::<FaceDown>();
+    sfx.write(Sfx::Click);
+
+    let Some(selected_entity) = selected_tile[player.0].take() else {
+        selected_tile[player.0] = Some(pressed_entity);
+        return;
+    };
+
+    let (_, selected_variant, selected_position, ..) = tiles.get(selected_entity).unwrap();
+
+    // As in the card game, the turn only passes on a mismatch.
+    if !pressed_variant.matches(selected_variant) {
+        flip_back.0[player.0] = Some((
+            [pressed_entity, selected_entity],
+            Timer::new(REVEAL, TimerMode::Once),
+        ));
+        sfx.write(Sfx::Mismatch);
+        players.pass();
+        return;
+    }
+
+    history.push_front(HistoryItem::ValidPair(pressed_entity, selected_entity));
+    commands.entity(pressed_entity).insert(marker::Hidden);
+    commands.entity(selected_entity).insert(marker::Hidden);
+
+    board_updated.write(BoardUpdated);
+    pair_removed.write(PairRemoved(player, *pressed_position, *selected_position));
+    sfx.write(Sfx::Match);
+    players.scored(player);
+
+    let remaining = tiles.iter().filter(|(.., other)| **other == player).count();
+
+    if remaining == 2 {
+        next_state.set(players.finish(player, true));
+    }
+}
+
+fn flip_back(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut flip_back: ResMut<FlipBack>,
+    mut frame_budget: ResMut<FrameBudget>,
+) {
+    for pending in &mut flip_back.0 {
+        let Some((pair, timer)) = pending else {
+            continue;
+        };
+
+        frame_budget.request("flip back");
+
+        if timer.tick(time.delta()).is_finished() {
+            for entity in *pair {
+                commands.entity(entity).insert(FaceDown);
+            }
+            *pending = None;
+        }
+    }
+}
+
+/// Turns face down any tile left face up without being selected, e.g. after an undo cleared the
+/// selection.
+fn turn_unselected(
+    mut commands: Commands,
+    tiles: Query<(Entity, &players::Player), (InPlay, Without<FaceDown>)>,
+    selected_tile: Res<SelectedTile>,
+    flip_back: Res<FlipBack>,
+    rules: Res<Rules>,
+) {
+    if rules.mode() != Mode::Memory {
+        return;
+    }
+
+    for (entity, player) in tiles {
+        let revealed = flip_back.0[player.0]
+            .as_ref()
+            .is_some_and(|(pair, _)| pair.contains(&entity));
+
+        if selected_tile[player.0] != Some(entity) && !revealed {
+            commands.entity(entity).insert(FaceDown);
+        }
+    }
+}
+
+/// Removed pairs stay on the board face up, even though [super::Undo::run] and
+/// [super::Redo::run] hide them like in the other modes.
+fn remove_pair(
+    on_add: On<Add, marker::Hidden>,
+    mut commands: Commands,
+    mut tiles: Query<&mut Visibility, With<tile::Marker<0>>>,
+    rules: Res<Rules>,
+    state: Res<State<InGame>>,
+) {
+    if rules.mode() != Mode::Memory || *state.get() != InGame::Running {
+        return;
+    }
+
+    if let Ok(mut visibility) = tiles.get_mut(on_add.entity) {
+        *visibility = Visibility::Inherited;
+        commands.entity(on_add.entity).remove::<FaceDown>();
+    }
+}
+
+/// Tiles are turned face down as they are dealt, and when a pair is restored by undo.
+fn restore_pair(
+    on_remove: On<Remove, marker::Hidden>,
+    mut commands: Commands,
+    tiles: Query<(), With<tile::Marker<0>>>,
+    rules: Res<Rules>,
+) {
+    if rules.mode() == Mode::Memory && tiles.contains(on_remove.entity) {
+        commands.entity(on_remove.entity).insert(FaceDown);
+    }
+}
+
+type ShownTile = (
+    &'static tile::Variant,
+    Has<FaceDown>,
+    &'static Children,
+    Option<&'static mut AccessibilityNode>,
+);
+
+/// Keeps the faces, and what screen readers announce, in line with [FaceDown]. Faces are respawned
+/// whenever the theme changes, so this can not be done once when turning a tile.
+fn show_faces(
+    tiles: Query<ShownTile, With<tile::Marker<0>>>,
+    mut faces: Query<&mut Visibility, With<tile::Face>>,
+    theme: theme::Active,
+    rules: Res<Rules>,
+    reveal: Res<Reveal>,
+    mut tr: Tr,
+) {
+    let changed = tr.changed() || reveal.is_changed();
+
+    if rules.mode() != Mode::Memory || !theme.is_loaded() {
+        return;
+    }
+
+    for (variant, face_down, children, accessibility) in tiles {
+        let visibility = if face_down && !**reveal {
+            Visibility::Hidden
+        } else {
+            Visibility::Inherited
+        };
+
+        let mut turned = changed;
+
+        for child in children {
+            if let Ok(mut face) = faces.get_mut(*child)
+                && *face != visibility
+            {
+                *face = visibility;
+                turned = true;
+            }
+        }
+
+        if let Some(mut accessibility) = accessibility
+            && turned
+        {
+            if face_down {
+                accessibility.set_label(tr.get("a11y.face_down"));
+            } else {
+                accessibility.set_label(tr.get(theme.get().face_key(*variant)));
+            }
+        }
+    }
+}
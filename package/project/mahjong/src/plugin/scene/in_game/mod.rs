@@ -1,21 +1,42 @@
-use crate::plugin::{default::DefaultWinitSettings, scene::main_menu::MainMenu};
+mod accessibility;
+pub(crate) mod atlas;
+mod board;
+pub(crate) mod bot;
+#[cfg(feature = "console")]
+mod console;
+mod free;
+pub(crate) mod generator;
+mod highlight;
+mod hud;
+#[cfg(feature = "inspector")]
+mod inspector;
+pub(crate) mod memory;
+mod online;
+mod players;
+pub(crate) mod rules;
+pub(crate) mod shisen;
+mod stats;
+pub(crate) mod theme;
+
+use crate::plugin::{
+    audio::{self, Sfx},
+    frame_budget::FrameBudget,
+    locale::Localized,
+    platform::{self, Platform, PlatformTrait},
+    scene::{
+        loading::Manifest,
+        main_menu::{MainMenu, settings},
+    },
+};
 use bevy::{
+    app::Propagate,
+    diagnostic::{DiagnosticPath, Diagnostics},
+    ecs::system::SystemParam,
     input::keyboard::KeyCode,
+    platform::time::Instant,
     prelude::*,
-    sprite::{Anchor, Text2dShadow},
-    winit::{UpdateMode, WinitSettings},
-};
-use platform::{Platform, PlatformPlugin, PlatformTrait};
-use rand::{
-    SeedableRng,
-    rngs::StdRng,
-    seq::{IteratorRandom, SliceRandom},
-};
-use std::{
-    cmp::Ordering,
-    collections::{HashMap, VecDeque},
-    time::Duration,
 };
+use std::{collections::VecDeque, time::Duration};
 
 pub struct Plugin;
 
@@ -24,10 +45,31 @@ impl bevy::prelude::Plugin for Plugin {
         &self,
         app: &mut App,
     ) {
-        app.add_plugins(PlatformPlugin)
+        app.add_plugins((
+            highlight::Plugin,
+            accessibility::Plugin,
+            atlas::Plugin,
+            board::Plugin,
+            free::Plugin,
+            rules::Plugin,
+            shisen::Plugin,
+            memory::Plugin,
+            stats::Plugin,
+            players::Plugin,
+            online::Plugin,
+            bot::Plugin,
+        ));
+
+        #[cfg(feature = "console")]
+        app.add_plugins(console::Plugin);
+        #[cfg(feature = "inspector")]
+        app.add_plugins(inspector::Plugin);
+
+        app.add_plugins(theme::Plugin)
             .add_sub_state::<InGame>()
             .add_message::<HelpMsg>()
             .add_message::<BoardUpdated>()
+            .add_message::<PairRemoved>()
             .insert_resource(Timer(bevy::time::Timer::new(
                 Duration::from_millis(10),
                 TimerMode::Repeating,
@@ -37,20 +79,21 @@ impl bevy::prelude::Plugin for Plugin {
             .insert_resource(SelectedTile::default())
             .insert_resource(History::default())
             .insert_resource(HelpEnabled::default())
+            .insert_resource(hud::Scale::default())
             .add_systems(OnEnter(InGame::Root), startup)
+            .add_systems(Update, wait_for_theme.run_if(in_state(InGame::Root)))
             .add_systems(
                 OnEnter(InGame::Init),
                 (
-                    update_winit_settings,
                     spawn_background,
                     (bind_tiles_to_positions, spawn_tiles).chain(),
-                    spawn_buttons,
-                    spawn_info,
+                    (hud::spawn_anchors, spawn_buttons, spawn_info).chain(),
+                    audio::play(Sfx::Shuffle),
                 ),
             )
             .add_systems(
                 Update,
-                resize.run_if(
+                (resize, hud::scale).run_if(
                     in_state(InGame::Running)
                         .or(in_state(InGame::Init))
                         .or(in_state(InGame::Victory).or(in_state(InGame::Defeat))),
@@ -60,7 +103,9 @@ impl bevy::prelude::Plugin for Plugin {
                 Update,
                 (
                     progressively_show_tiles.run_if(in_state(InGame::Init)),
-                    update_move_count.run_if(in_state(InGame::Running)),
+                    (free::update, update_move_count)
+                        .chain()
+                        .run_if(in_state(InGame::Running)),
                 ),
             )
             .add_systems(
@@ -70,249 +115,20 @@ impl bevy::prelude::Plugin for Plugin {
                     redo_keyboard,
                     help_keyboard,
                     help_toggle,
-                    help,
+                    free::indicator_keyboard,
+                    (theme::select_keyboard, apply_theme, highlight::apply).chain(),
                 )
                     .run_if(in_state(InGame::Running)),
             )
             .add_systems(Update, poll_new_seed.run_if(in_state(InGame::Running)))
+            .add_systems(Update, measure_tiles)
+            .add_systems(OnEnter(InGame::Victory), audio::play(Sfx::Victory))
+            .add_systems(OnEnter(InGame::Defeat), audio::play(Sfx::Defeat))
             .add_systems(Update, spawn_finished.run_if(in_state(InGame::Victory)))
             .add_systems(Update, spawn_defeat.run_if(in_state(InGame::Defeat)));
     }
 }
 
-mod platform {
-    use bevy::prelude::*;
-    pub use implementation::Platform;
-
-    pub struct PlatformPlugin;
-
-    impl bevy::prelude::Plugin for PlatformPlugin {
-        fn build(
-            &self,
-            app: &mut App,
-        ) {
-            app.add_message::<SeedChanged>()
-                .add_plugins(implementation::PlatformPlugin);
-        }
-    }
-
-    pub trait Observer<T> {
-        fn observe(
-            &mut self,
-            object: T,
-        );
-    }
-
-    #[derive(Message)]
-    pub struct SeedChanged {
-        pub new: u64,
-    }
-
-    pub trait PlatformTrait: Resource + Default {
-        const DEFAULT_MSG: &'static str = "Not implemented for this platform!";
-
-        type ObserverItem;
-
-        fn rng_seed_observe(
-            &mut self,
-            _observer: &mut dyn Observer<Self::ObserverItem>,
-        ) {
-            debug!("rng_seed_observe {}", Self::DEFAULT_MSG);
-        }
-
-        fn rng_seed_get(&self) -> Option<u64> {
-            debug!("rng_seed_get {}", Self::DEFAULT_MSG);
-            None
-        }
-
-        fn rng_seed_set(
-            &self,
-            seed: u64,
-        ) {
-            debug!("rng_seed_set {}", Self::DEFAULT_MSG);
-        }
-    }
-
-    /// NATIVE
-    #[cfg(not(target_arch = "wasm32"))]
-    mod implementation {
-        use super::*;
-
-        pub struct PlatformPlugin;
-
-        impl bevy::prelude::Plugin for PlatformPlugin {
-            fn build(
-                &self,
-                app: &mut App,
-            ) {
-                let mut platform = Platform::default();
-                app.insert_resource(platform);
-            }
-        }
-
-        #[derive(Resource, Default)]
-        pub struct Platform;
-
-        impl PlatformTrait for Platform {
-            type ObserverItem = ();
-        }
-
-        impl Platform {}
-    }
-
-    /// WASM
-    #[cfg(target_arch = "wasm32")]
-    mod implementation {
-        use super::*;
-        use std::cell::RefCell;
-        use std::rc::Rc;
-        use wasm_bindgen::JsCast;
-        use wasm_bindgen::prelude::*;
-        use web_sys::HashChangeEvent;
-
-        pub struct PlatformPlugin;
-
-        impl bevy::prelude::Plugin for PlatformPlugin {
-            fn build(
-                &self,
-                app: &mut App,
-            ) {
-                let mut platform = Platform::default();
-                let mut observer = HashObserver::default();
-                platform.rng_seed_observe(&mut observer);
-
-                app.insert_resource(platform)
-                    .insert_non_send_resource(observer)
-                    .add_systems(PreUpdate, poll_hash_changes);
-            }
-        }
-
-        #[derive(Resource, Default)]
-        pub struct Platform;
-
-        // Resource: NonSend
-        #[derive(Default)]
-        pub struct HashObserver {
-            pub closure: Option<Closure<dyn FnMut(HashChangeEvent)>>,
-            pub pending: Option<Rc<RefCell<Option<(String, String)>>>>,
-        }
-
-        impl
-            Observer<(
-                Closure<dyn FnMut(HashChangeEvent)>,
-                Rc<RefCell<Option<(String, String)>>>,
-            )> for HashObserver
-        {
-            fn observe(
-                &mut self,
-                object: (
-                    Closure<dyn FnMut(HashChangeEvent)>,
-                    Rc<RefCell<Option<(String, String)>>>,
-                ),
-            ) {
-                self.closure = Some(object.0);
-                self.pending = Some(object.1);
-            }
-        }
-
-        impl Drop for HashObserver {
-            fn drop(&mut self) {
-                if self.closure.is_some() {
-                    let window = web_sys::window().unwrap();
-                    window
-                        .remove_event_listener_with_callback(
-                            "hashchange",
-                            self.closure.take().unwrap().as_ref().unchecked_ref(),
-                        )
-                        .unwrap();
-                }
-            }
-        }
-
-        impl PlatformTrait for Platform {
-            type ObserverItem = (
-                Closure<dyn FnMut(HashChangeEvent)>,
-                Rc<RefCell<Option<(String, String)>>>,
-            );
-
-            fn rng_seed_observe(
-                &mut self,
-                observer: &mut dyn Observer<Self::ObserverItem>,
-            ) {
-                let window = web_sys::window().expect("no window found");
-                let pending = Rc::new(RefCell::new(None));
-
-                let closure = Closure::wrap(Box::new({
-                    let pending = Rc::clone(&pending);
-                    move |event: HashChangeEvent| {
-                        let url_old = event.old_url();
-                        let url_new = event.new_url();
-                        if let Some(hash_old) = url_old.split_once('#') {
-                            let hash_old = hash_old.1;
-                            let hash_new = url_new.split_once('#').unwrap().1;
-                            *pending.borrow_mut() =
-                                Some((hash_old.to_owned(), hash_new.to_owned()));
-                        }
-                    }
-                }) as Box<dyn FnMut(_)>);
-
-                window
-                    .add_event_listener_with_callback(
-                        "hashchange",
-                        closure.as_ref().unchecked_ref(),
-                    )
-                    .unwrap();
-
-                observer.observe((closure, pending));
-            }
-
-            fn rng_seed_get(&self) -> Option<u64> {
-                Self::get_fragment()
-            }
-
-            fn rng_seed_set(
-                &self,
-                seed: u64,
-            ) {
-                Self::set_fragment(&seed.to_string())
-            }
-        }
-
-        impl Platform {
-            fn get_fragment() -> Option<u64> {
-                let window = web_sys::window().expect("no global `window` exists");
-                let location = window.location();
-                location.hash().ok()?.trim_start_matches('#').parse().ok()
-            }
-
-            fn set_fragment(fragment_hash: &str) {
-                let window = web_sys::window().expect("no global `window` exists");
-                let location = window.location();
-
-                location
-                    .set_hash(format!("#{fragment_hash}").as_str())
-                    .expect("failed to set hash");
-            }
-        }
-
-        fn poll_hash_changes(
-            observer: NonSend<HashObserver>,
-            mut writer: MessageWriter<SeedChanged>,
-        ) {
-            if let Some(pending) = observer.pending.as_ref() {
-                let new = pending.borrow_mut().take();
-                if let Some((old, new)) = new {
-                    if old != new {
-                        debug!("New hash! ({new})");
-                        let new = new.parse().unwrap();
-                        writer.write(SeedChanged { new });
-                    }
-                }
-            }
-        }
-    }
-}
-
 fn spawn<'a>(
     commands: &'a mut Commands,
     bundle: impl Bundle,
@@ -325,7 +141,7 @@ fn spawn<'a>(
 #[derive(SubStates, Default, Debug, Hash, Eq, PartialEq, Clone)]
 #[source(MainMenu = MainMenu::Play)]
 #[states(scoped_entities)]
-enum InGame {
+pub(crate) enum InGame {
     #[default]
     Root,
     Init,
@@ -334,8 +150,14 @@ enum InGame {
     Defeat,
 }
 
+/// Seed the board was generated from, [None] until it is.
 #[derive(Resource, Deref, DerefMut, Default)]
-struct Seed(Option<u64>);
+pub(crate) struct Seed(Option<u64>);
+
+/// Tiles left on the board. Only measured once registered, i.e. with the `diagnostics` feature.
+pub const TILES: DiagnosticPath = DiagnosticPath::const_new("tiles");
+/// Time taken to generate the board (ms).
+pub const GENERATION: DiagnosticPath = DiagnosticPath::const_new("generation");
 
 #[derive(Resource, Deref, DerefMut, Default)]
 struct Timer(bevy::time::Timer);
@@ -343,8 +165,9 @@ struct Timer(bevy::time::Timer);
 #[derive(Resource, Deref, DerefMut, Default)]
 struct TilePositionVariantPairs(Vec<(tile::Position, tile::Variant)>);
 
+/// Selected tile of each [players::Player].
 #[derive(Resource, Deref, DerefMut, Default)]
-struct SelectedTile(Option<Entity>);
+struct SelectedTile([Option<Entity>; players::PLAYERS]);
 
 #[derive(Resource, Default, Deref, DerefMut, PartialEq, Eq)]
 struct HelpEnabled(bool);
@@ -355,10 +178,13 @@ struct HelpMsg;
 #[derive(Message)]
 struct BoardUpdated;
 
+/// Positions of a pair just removed from the board of a player.
+#[derive(Message)]
+struct PairRemoved(players::Player, tile::Position, tile::Position);
+
 #[derive(Clone)]
 enum HistoryItem {
     ValidPair(Entity, Entity),
-    Shuffle(Vec<(Entity, tile::Variant)>),
 }
 
 #[derive(Resource, Default)]
@@ -403,6 +229,11 @@ impl History {
     }
 }
 
+/// Tiles still on a board.
+type InPlay = (With<tile::Marker<0>>, Without<marker::Hidden>);
+/// Tiles removed from a board, until they are dealt or brought back by an undo.
+type Removed = (With<tile::Marker<0>>, With<marker::Hidden>);
+
 mod marker {
     use bevy::prelude::*;
 
@@ -411,37 +242,15 @@ mod marker {
 
     #[derive(Component)]
     pub struct Hidden;
-
-    #[derive(Component)]
-    pub struct Info;
 }
 
-mod tile {
-    use bevy::prelude::*;
-    use std::marker::PhantomData;
-
-    pub mod asset {
-        pub mod texture {
-            pub const TILE: &'static str = "misc/rev2/lowres/Tile2.png";
-            pub const ALLIANCE: &'static str = "misc/rev2/lowres/Alliance.png";
-            pub const HORDE: &'static str = "misc/rev2/lowres/Horde.png";
-            pub const FROSTMOURNE: &'static str = "misc/rev2/lowres/Frostmourne.png";
-            pub const ASHBRINGER: &'static str = "misc/rev2/lowres/Ashbringer.png";
-
-            pub const TILE_WIDTH: u32 = 962;
-            pub const TILE_HEIGHT: u32 = 1238;
-            pub const TILE_NO_BORDER_WIDTH: u32 = 872;
-            pub const TILE_NO_BORDER_HEIGHT: u32 = 1149;
-            pub const TILE_BORDER_LENGTH: u32 = 90;
-        }
-    }
-
-    pub const DEFAULT_COLOR: Color = Color::Hsla(Hsla {
-        hue: 60.0,
-        saturation: 0.2,
-        lightness: 1.25,
-        alpha: 1.0,
-    });
+pub(crate) mod tile {
+    use super::{
+        atlas::FaceAtlas,
+        theme::{Art, Theme, TileBody},
+    };
+    use bevy::{prelude::*, sprite::Anchor};
+    use std::{marker::PhantomData, ops::Range};
 
     #[derive(Bundle)]
     pub struct Tile {
@@ -454,6 +263,14 @@ mod tile {
     #[derive(Component)]
     pub struct Marker<const DEPTH: u32>;
 
+    /// Child holding the face art of a tile.
+    #[derive(Component)]
+    pub struct Face;
+
+    /// Child drawing the drop shadow of a tile that is not on the ground layer.
+    #[derive(Component)]
+    pub struct Shadow;
+
     #[derive(Component, Deref, DerefMut, Clone, Copy, Debug, PartialEq, Eq, Hash)]
     pub struct Position(pub UVec3);
 
@@ -480,7 +297,6 @@ mod tile {
         pub const TILE_VARIANT_GROUP_SIZE: usize = 4;
         pub const ROWS: usize = 8;
         pub const COLUMNS: usize = 15;
-        pub const LAYERS: usize = 5;
         pub const TILE_GRID_SIZE: usize = 2;
     }
 
@@ -536,7 +352,7 @@ mod tile {
             let column = match layer {
                 0 => {
                     match row {
-                        0 => 1 + self.counter - 0,
+                        0 => 1 + self.counter,
                         1 => 3 + self.counter - 12,
                         2 => 2 + self.counter - 20,
                         3 => 1 + self.counter - 30,
@@ -583,505 +399,295 @@ mod tile {
 
             let row = row * self.tile_grid_size.y;
             let column = column * self.tile_grid_size.x;
-            let local_position = Position(UVec3::new(column as u32, row as u32, layer as u32));
+            let local_position = Position(UVec3::new(column, row, layer as u32));
             self.counter += 1;
-            return Some(local_position);
+            Some(local_position)
+        }
+    }
+
+    /// Screen space measurements of the tiles, derived from the camera projection and the
+    /// [TileBody] of the active theme.
+    pub struct Geometry {
+        /// Size of the front of a tile.
+        pub size: Vec2,
+        /// Size of a tile including its border.
+        pub size_full: Vec2,
+        offset: Vec3,
+        layer_offset: Vec3,
+    }
+
+    impl Geometry {
+        const COLUMN_DEPTH_OFFSET: f32 = -0.1;
+
+        pub fn new(
+            projection: &OrthographicProjection,
+            body: &TileBody,
+        ) -> Self {
+            let rows = PositionGenerator::<Turtle>::ROWS as f32;
+            let columns = PositionGenerator::<Turtle>::COLUMNS as f32;
+
+            let size = Vec2::new(
+                (projection.area.height() / rows) * 0.85,
+                projection.area.height() / rows,
+            );
+            let size_full = Vec2::new(
+                (size.x / body.face_size.x as f32) * body.size.x as f32,
+                (size.y / body.face_size.y as f32) * body.size.y as f32,
+            );
+            let size_ratio = size.y / body.face_size.y as f32;
+            let border_length_scaled = body.border_length as f32 * size_ratio;
+            let offset = Vec3::new(
+                -(size.x * columns / 2.0) + size.x * 1.0 - border_length_scaled / 2.0,
+                -projection.area.height() / 2.0 + size_full.y * 0.5 - border_length_scaled,
+                0.0,
+            );
+
+            Self {
+                size,
+                size_full,
+                offset,
+                layer_offset: Vec3 {
+                    x: border_length_scaled,
+                    y: border_length_scaled,
+                    ..default()
+                },
+            }
+        }
+
+        pub fn translation(
+            &self,
+            pos: &Position,
+        ) -> Vec3 {
+            let tile_grid_size = PositionGenerator::<Turtle>::TILE_GRID_SIZE as u32;
+            let columns = PositionGenerator::<Turtle>::COLUMNS as f32;
+
+            let default_depth = Vec3::default().with_z(100.0);
+            let column_depth_offset_factor = Vec3::default().with_z(Self::COLUMN_DEPTH_OFFSET);
+            let row_depth_offset_factor = column_depth_offset_factor * columns;
+            let layer_depth_offset_factor = Vec3::default().with_z(10.0);
+
+            let special = match pos.x / tile_grid_size {
+                0 => Vec3::default().with_z(-column_depth_offset_factor.z * columns),
+                13 | 14 => Vec3::default().with_z(column_depth_offset_factor.z * columns),
+                _ => Vec3::default(),
+            };
+
+            (((pos.as_vec3() / tile_grid_size as f32) * self.size.extend(1.0)) + self.offset)
+                + default_depth
+                + (self.layer_offset * pos.z as f32)
+                + (column_depth_offset_factor * pos.x as f32)
+                + (row_depth_offset_factor * pos.y as f32)
+                + (layer_depth_offset_factor * pos.z as f32)
+                + special
+        }
+
+        pub fn shadow(
+            &self,
+            pos: &Position,
+            texture: Handle<Image>,
+            color: Color,
+        ) -> impl Bundle {
+            (
+                Shadow,
+                Sprite {
+                    custom_size: Some(self.size_full),
+                    color,
+                    ..Sprite::from_image(texture)
+                },
+                self.shadow_transform(pos),
+                Anchor::BOTTOM_LEFT,
+            )
+        }
+
+        pub fn shadow_transform(
+            &self,
+            pos: &Position,
+        ) -> Transform {
+            Transform {
+                scale: Vec3 {
+                    x: 1.2,
+                    y: 1.03,
+                    ..Vec3::splat(1.0)
+                },
+                translation: Vec3 {
+                    x: -self.size_full.x / 2.0,
+                    y: -self.size_full.y / 2.0,
+                    z: Self::COLUMN_DEPTH_OFFSET * pos.x as f32,
+                },
+                ..default()
+            }
+        }
+
+        /// Offset of the face art, centering it on the front of the tile.
+        pub fn face_offset(&self) -> Vec3 {
+            self.layer_offset / 2.0
         }
     }
 
     #[derive(Component, Deref, DerefMut, Clone, Copy, Eq, PartialEq, Debug)]
     pub struct Variant(pub u32);
 
+    /// What a [Variant] can be matched with.
+    #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+    pub enum MatchGroup {
+        /// Only identical variants match.
+        Exact(u32),
+        /// Any variant of the group matches, e.g. any flower matches any flower.
+        Any(u32),
+    }
+
     impl Variant {
+        /// Number of regular variants, each dealt as [PositionGenerator::TILE_VARIANT_GROUP_SIZE]
+        /// identical tiles.
+        pub const REGULAR: u32 = 34;
+        /// Unique tiles which match any other tile of the same group.
+        pub const GROUPS: [Range<u32>; 2] = [Self::FLOWERS, Self::SEASONS];
+        pub const FLOWERS: Range<u32> = 34..38;
+        pub const SEASONS: Range<u32> = 38..42;
+
+        pub fn match_group(&self) -> MatchGroup {
+            match Self::GROUPS
+                .iter()
+                .position(|group| group.contains(&self.0))
+            {
+                Some(group) => MatchGroup::Any(group as u32),
+                None => MatchGroup::Exact(self.0),
+            }
+        }
+
+        pub fn matches(
+            &self,
+            other: &Variant,
+        ) -> bool {
+            self.match_group() == other.match_group()
+        }
+
+        /// Matching pairs of variants for a board of `tiles` tiles. Every
+        /// [PositionGenerator::TILE_VARIANT_GROUP_SIZE] tiles make up either one regular variant or
+        /// one of [Variant::GROUPS]. Larger boards repeat the set.
+        pub fn deal(tiles: usize) -> Vec<(Variant, Variant)> {
+            const GROUP_SIZE: usize = PositionGenerator::<Turtle>::TILE_VARIANT_GROUP_SIZE;
+            let set_size = Variant::REGULAR + Variant::GROUPS.len() as u32;
+
+            let mut pairs = Vec::with_capacity(tiles / 2);
+
+            for index in 0..(tiles / GROUP_SIZE) as u32 {
+                let index = index % set_size;
+
+                if index < Variant::REGULAR {
+                    for _ in 0..GROUP_SIZE / 2 {
+                        pairs.push((Variant(index), Variant(index)));
+                    }
+                } else {
+                    let group = Variant::GROUPS[(index - Variant::REGULAR) as usize].clone();
+                    let members: Vec<Variant> = group.map(Variant).collect();
+
+                    for pair in members.chunks(2) {
+                        pairs.push((pair[0], pair[1]));
+                    }
+                }
+            }
+
+            pairs
+        }
+
         pub fn insert_sprite_as_child(
-            asset_server: &Res<AssetServer>,
+            faces: &FaceAtlas,
+            theme: &Theme,
             entity_commands: &mut EntityCommands,
             variant: u32,
             max_size: &Vec2,
             offset: &Vec3,
         ) {
-            let large = max_size * 0.8;
-            let medium = large * 0.5;
-            let medium2 = large * 0.7;
-            let small = large * 0.40;
-
-            let alliance: Handle<Image> = asset_server.load(asset::texture::ALLIANCE);
-            let horde: Handle<Image> = asset_server.load(asset::texture::HORDE);
-            let frostmourne: Handle<Image> = asset_server.load(asset::texture::FROSTMOURNE);
-            let ashbringer: Handle<Image> = asset_server.load(asset::texture::ASHBRINGER);
+            let Some(face) = theme.faces.get(variant as usize) else {
+                warn!("Unsupported variant!");
+                return;
+            };
 
             let common = (
+                Face,
                 Transform::default().with_translation(Vec3::default().with_z(0.1) + offset),
                 Visibility::Inherited,
             );
 
-            fn template(
-                x: f32,
-                y: f32,
-                z: f32,
-                size: Vec2,
-                image: Handle<Image>,
-                shading: Option<Color>,
-            ) -> impl Bundle {
-                (
-                    Transform {
-                        translation: Vec3 { x, y, z },
-                        ..default()
-                    },
+            if let Some(sprite) = faces.get(variant) {
+                entity_commands.with_child((
+                    common,
                     Sprite {
-                        custom_size: Some(size),
-                        color: shading.unwrap_or_default(),
-                        ..Sprite::from_image(image)
+                        custom_size: Some(max_size * sprite.size),
+                        ..Sprite::from_atlas_image(sprite.image.clone(), sprite.atlas.clone())
                     },
-                )
+                ));
+            } else if let Art::Text { text, size, color } = &face.art {
+                entity_commands.with_child((
+                    common,
+                    Text2d(text.clone()),
+                    TextFont {
+                        font_size: max_size.y * size,
+                        ..default()
+                    },
+                    TextColor(*color),
+                    TextLayout::new_with_justify(Justify::Center),
+                ));
+            } else {
+                warn!("Face of variant {variant} was not baked!");
             }
+        }
+    }
 
-            match variant {
-                0 | 1 | 2 | 3 => {
-                    let image = match variant {
-                        0 => alliance,
-                        1 => horde,
-                        2 => frostmourne,
-                        3 => ashbringer,
-                        _ => unreachable!(),
-                    };
-                    entity_commands.with_child((
-                        common,
-                        children![template(0.0, 0.0, 0.0, large.clone(), image.clone(), None),],
-                    ));
-                },
-                4 | 5 | 6 | 7 => {
-                    let (image, size, inverted) = match variant {
-                        4 => (alliance, medium, 1.0),
-                        5 => (horde, medium, 1.0),
-                        6 => (frostmourne, medium2, 1.0),
-                        7 => (ashbringer, medium2, -1.0),
-                        _ => unreachable!(),
-                    };
+    #[cfg(test)]
+    mod tests {
+        use super::*;
 
-                    entity_commands.with_child((
-                        common,
-                        children![
-                            template(
-                                -max_size.x / 8.0 * inverted,
-                                max_size.y / 8.0,
-                                0.0,
-                                size.clone(),
-                                image.clone(),
-                                None,
-                            ),
-                            template(
-                                max_size.x / 8.0 * inverted,
-                                -max_size.y / 8.0,
-                                0.1,
-                                size.clone(),
-                                image.clone(),
-                                None,
-                            ),
-                        ],
-                    ));
-                },
-                8 | 9 | 10 | 11 => {
-                    let (image, size, inverted) = match variant {
-                        8 => (alliance, small, 1.0),
-                        9 => (horde, small, 1.0),
-                        10 => (frostmourne, medium, 1.0),
-                        11 => (ashbringer, medium, -1.0),
-                        _ => unreachable!(),
-                    };
-                    entity_commands.with_child((
-                        common,
-                        children![
-                            template(
-                                -max_size.x / 5.0 * inverted,
-                                max_size.y / 5.0,
-                                0.0,
-                                size.clone(),
-                                image.clone(),
-                                None,
-                            ),
-                            template(0.0, 0.0, 0.1, size.clone(), image.clone(), None,),
-                            template(
-                                max_size.x / 5.0 * inverted,
-                                -max_size.y / 5.0,
-                                0.3,
-                                size.clone(),
-                                image.clone(),
-                                None,
-                            ),
-                        ],
-                    ));
-                },
-                12 | 13 | 14 | 15 => {
-                    let (image, size) = match variant {
-                        12 => (alliance, small),
-                        13 => (horde, small),
-                        14 => (frostmourne, medium),
-                        15 => (ashbringer, medium),
-                        _ => unreachable!(),
-                    };
-                    entity_commands.with_child((
-                        common,
-                        children![
-                            template(
-                                -max_size.x / 7.0,
-                                -max_size.y / 7.0,
-                                0.0,
-                                size.clone(),
-                                image.clone(),
-                                None,
-                            ),
-                            template(
-                                -max_size.x / 7.0,
-                                max_size.y / 7.0,
-                                0.1,
-                                size.clone(),
-                                image.clone(),
-                                None,
-                            ),
-                            template(
-                                max_size.x / 7.0,
-                                -max_size.y / 7.0,
-                                0.2,
-                                size.clone(),
-                                image.clone(),
-                                None,
-                            ),
-                            template(
-                                max_size.x / 7.0,
-                                max_size.y / 7.0,
-                                0.3,
-                                size.clone(),
-                                image.clone(),
-                                None,
-                            ),
-                        ],
-                    ));
-                },
-                16 | 17 | 18 | 19 => {
-                    let (image, color, size) = match variant {
-                        16 => (alliance, Color::BLACK, small),
-                        17 => (horde, Color::BLACK, small),
-                        18 => (frostmourne, Color::BLACK, small),
-                        19 => (ashbringer, Color::BLACK, small),
-                        _ => unreachable!(),
-                    };
-                    entity_commands.with_child((
-                        common,
-                        children![
-                            template(
-                                -max_size.x / 5.0,
-                                -max_size.y / 5.0,
-                                0.0,
-                                size.clone(),
-                                image.clone(),
-                                None,
-                            ),
-                            template(
-                                -max_size.x / 5.0,
-                                max_size.y / 5.0,
-                                0.1,
-                                size.clone(),
-                                image.clone(),
-                                None,
-                            ),
-                            template(0.0, 0.0, 0.2, size.clone(), image.clone(), None,),
-                            template(
-                                max_size.x / 5.0,
-                                -max_size.y / 5.0,
-                                0.3,
-                                size.clone(),
-                                image.clone(),
-                                None,
-                            ),
-                            template(
-                                max_size.x / 5.0,
-                                max_size.y / 5.0,
-                                0.4,
-                                size.clone(),
-                                image.clone(),
-                                None,
-                            ),
-                        ],
-                    ));
-                },
-                20 | 21 | 22 | 23 => {
-                    let (image, color, size) = match variant {
-                        20 => (alliance, Color::BLACK, small),
-                        21 => (horde, Color::BLACK, small),
-                        22 => (frostmourne, Color::BLACK, small),
-                        23 => (ashbringer, Color::BLACK, small),
-                        _ => unreachable!(),
-                    };
-                    entity_commands.with_child((
-                        common,
-                        children![
-                            template(
-                                -max_size.x / 6.0,
-                                -max_size.y / 5.0,
-                                0.0,
-                                size.clone(),
-                                image.clone(),
-                                None,
-                            ),
-                            template(
-                                -max_size.x / 6.0,
-                                0.0,
-                                0.1,
-                                size.clone(),
-                                image.clone(),
-                                None,
-                            ),
-                            template(
-                                -max_size.x / 6.0,
-                                max_size.y / 5.0,
-                                0.2,
-                                size.clone(),
-                                image.clone(),
-                                None,
-                            ),
-                            template(
-                                max_size.x / 6.0,
-                                -max_size.y / 5.0,
-                                0.3,
-                                size.clone(),
-                                image.clone(),
-                                None,
-                            ),
-                            template(
-                                max_size.x / 6.0,
-                                0.0,
-                                0.4,
-                                size.clone(),
-                                image.clone(),
-                                None,
-                            ),
-                            template(
-                                max_size.x / 6.0,
-                                max_size.y / 5.0,
-                                0.5,
-                                size.clone(),
-                                image.clone(),
-                                None,
-                            ),
-                        ],
-                    ));
-                },
-                24 | 25 | 26 | 27 => {
-                    let (image, color, size) = match variant {
-                        24 => (alliance, Color::BLACK, small),
-                        25 => (horde, Color::BLACK, small),
-                        26 => (frostmourne, Color::BLACK, small),
-                        27 => (ashbringer, Color::BLACK, small),
-                        _ => unreachable!(),
-                    };
-                    entity_commands.with_child((
-                        common,
-                        children![
-                            template(
-                                -max_size.x / 5.0,
-                                -max_size.y / 5.0,
-                                0.0,
-                                size.clone(),
-                                image.clone(),
-                                None,
-                            ),
-                            template(
-                                0.0,
-                                -max_size.y / 5.0,
-                                0.1,
-                                size.clone(),
-                                image.clone(),
-                                None,
-                            ),
-                            template(
-                                max_size.x / 5.0,
-                                -max_size.y / 5.0,
-                                0.2,
-                                size.clone(),
-                                image.clone(),
-                                None,
-                            ),
-                            template(
-                                -max_size.x / 5.0,
-                                0.0,
-                                0.3,
-                                size.clone(),
-                                image.clone(),
-                                None,
-                            ),
-                            template(0.0, 0.0, 0.4, size.clone(), image.clone(), None,),
-                            template(
-                                max_size.x / 5.0,
-                                0.0,
-                                0.5,
-                                size.clone(),
-                                image.clone(),
-                                None,
-                            ),
-                            template(
-                                0.0,
-                                max_size.y / 5.0,
-                                0.6,
-                                size.clone(),
-                                image.clone(),
-                                Some(color),
-                            ),
-                        ],
-                    ));
-                },
-                28 | 29 | 30 | 31 => {
-                    let (image, color, size) = match variant {
-                        28 => (alliance, Color::BLACK, small),
-                        29 => (horde, Color::BLACK, small),
-                        30 => (frostmourne, Color::BLACK, small),
-                        31 => (ashbringer, Color::BLACK, small),
-                        _ => unreachable!(),
-                    };
-                    entity_commands.with_child((
-                        common,
-                        children![
-                            template(
-                                -max_size.x / 5.0,
-                                max_size.y / 5.0,
-                                0.7,
-                                size.clone(),
-                                image.clone(),
-                                None,
-                            ),
-                            template(
-                                0.0,
-                                max_size.y / 5.0,
-                                0.6,
-                                size.clone(),
-                                image.clone(),
-                                None,
-                            ),
-                            template(
-                                max_size.x / 5.0,
-                                max_size.y / 5.0,
-                                0.5,
-                                size.clone(),
-                                image.clone(),
-                                None,
-                            ),
-                            template(
-                                -max_size.x / 5.0,
-                                0.0,
-                                0.4,
-                                size.clone(),
-                                image.clone(),
-                                None,
-                            ),
-                            template(0.0, 0.0, 0.3, size.clone(), image.clone(), None,),
-                            template(
-                                max_size.x / 5.0,
-                                0.0,
-                                0.2,
-                                size.clone(),
-                                image.clone(),
-                                None,
-                            ),
-                            template(
-                                -max_size.x / 5.0,
-                                -max_size.y / 5.0,
-                                0.1,
-                                size.clone(),
-                                image.clone(),
-                                Some(color),
-                            ),
-                            template(
-                                max_size.x / 5.0,
-                                -max_size.y / 5.0,
-                                0.0,
-                                size.clone(),
-                                image.clone(),
-                                Some(color),
-                            ),
-                        ],
-                    ));
-                },
-                32 | 33 | 34 | 35 => {
-                    let (image, color, size) = match variant {
-                        32 => (alliance, Color::BLACK, small),
-                        33 => (horde, Color::BLACK, small),
-                        34 => (frostmourne, Color::BLACK, small),
-                        35 => (ashbringer, Color::BLACK, small),
-                        _ => unreachable!(),
-                    };
-                    entity_commands.with_child((
-                        common,
-                        children![
-                            template(
-                                -max_size.x / 5.0,
-                                max_size.y / 5.0,
-                                0.6,
-                                size.clone(),
-                                image.clone(),
-                                None,
-                            ),
-                            template(
-                                0.0,
-                                max_size.y / 5.0,
-                                0.7,
-                                size.clone(),
-                                image.clone(),
-                                None,
-                            ),
-                            template(
-                                max_size.x / 5.0,
-                                max_size.y / 5.0,
-                                0.8,
-                                size.clone(),
-                                image.clone(),
-                                None,
-                            ),
-                            template(
-                                -max_size.x / 5.0,
-                                0.0,
-                                0.3,
-                                size.clone(),
-                                image.clone(),
-                                None,
-                            ),
-                            template(0.0, 0.0, 0.4, size.clone(), image.clone(), None,),
-                            template(
-                                max_size.x / 5.0,
-                                0.0,
-                                0.5,
-                                size.clone(),
-                                image.clone(),
-                                None,
-                            ),
-                            template(
-                                -max_size.x / 5.0,
-                                -max_size.y / 5.0,
-                                0.0,
-                                size.clone(),
-                                image.clone(),
-                                None,
-                            ),
-                            template(
-                                0.0,
-                                -max_size.y / 5.0,
-                                0.1,
-                                size.clone(),
-                                image.clone(),
-                                None,
-                            ),
-                            template(
-                                max_size.x / 5.0,
-                                -max_size.y / 5.0,
-                                0.2,
-                                size.clone(),
-                                image.clone(),
-                                None,
-                            ),
-                        ],
-                    ));
-                },
-                _ => warn!("Unsupported variant!"),
-            };
+        /// Every variant, regular or not.
+        const VARIANTS: Range<u32> = 0..Variant::SEASONS.end;
+
+        #[test]
+        fn groups_match_within() {
+            for group in Variant::GROUPS {
+                for a in group.clone() {
+                    for b in VARIANTS {
+                        assert_eq!(
+                            Variant(a).matches(&Variant(b)),
+                            group.contains(&b),
+                            "{a} and {b}"
+                        );
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn regular_match_exactly() {
+            for a in 0..Variant::REGULAR {
+                for b in VARIANTS {
+                    assert_eq!(Variant(a).matches(&Variant(b)), a == b, "{a} and {b}");
+                }
+            }
+        }
+
+        #[test]
+        fn deal_matching_pairs() {
+            // Shisen-Sho, the turtle and two turtles.
+            for tiles in [84, 144, 288] {
+                let pairs = Variant::deal(tiles);
+
+                assert_eq!(pairs.len(), tiles / 2);
+                assert!(pairs.iter().all(|(a, b)| a.matches(b)), "{pairs:?}");
+            }
+
+            // A whole set has every flower and season once.
+            let mut variants: Vec<u32> = Variant::deal(144)
+                .into_iter()
+                .flat_map(|(a, b)| [a.0, b.0])
+                .filter(|variant| *variant >= Variant::REGULAR)
+                .collect();
+            variants.sort();
+
+            assert_eq!(
+                variants,
+                (Variant::REGULAR..VARIANTS.end).collect::<Vec<_>>()
+            );
         }
     }
 }
@@ -1089,24 +695,33 @@ mod tile {
 mod info {
     use bevy::prelude::*;
 
-    pub mod asset {
-        pub const INFO: &'static str = "misc/rev2/lowres/StoneSlab.png";
-    }
-
-    #[derive(Component)]
-    pub struct ResizeData(pub Vec2, pub bool);
+    /// Tint of the slab behind an info.
+    pub const SLAB_COLOR: Color = Color::hsl(0.0, 0.0, 0.7);
 
     #[derive(Component, Clone, PartialEq)]
     pub enum Marker {
         Moves,
+        Time,
+        Elapsed,
+        Undos,
+        /// Pairs removed by a player.
+        Score(usize),
+        /// Moves left on the board of a player, in versus games.
+        PlayerMoves(usize),
     }
 
     impl Marker {
-        pub fn as_string(&self) -> &'static str {
+        /// Message key of the label, see [crate::plugin::locale].
+        pub fn key(&self) -> &'static str {
             use Marker::*;
 
             match self {
-                Moves => "Moves:\n",
+                Moves => "info.moves",
+                Time => "info.time",
+                Elapsed => "info.elapsed",
+                Undos => "info.undos",
+                Score(player) => ["info.score_1", "info.score_2"][*player],
+                PlayerMoves(player) => ["info.moves_1", "info.moves_2"][*player],
             }
         }
     }
@@ -1115,37 +730,45 @@ mod info {
 mod button {
     use bevy::prelude::*;
 
-    pub mod asset {
-        pub const BUTTON: &'static str = "misc/rev2/lowres/button-atlas_1998x429.png";
-    }
-
-    #[derive(Component)]
-    pub struct ResizeData(pub Vec2, pub bool);
-
     #[derive(Component, Clone, PartialEq)]
     pub enum Marker {
         Undo,
         Redo,
         Help,
         NewGame,
+        Settings,
     }
 
     impl Marker {
-        pub fn as_string(&self) -> &'static str {
+        /// Message key of the text, see [crate::plugin::locale].
+        pub fn key(&self) -> &'static str {
             use Marker::*;
 
             match self {
-                Undo => "[U]ndo",
-                Redo => "[R]edo",
-                Help => "[H]elp",
-                NewGame => "NewGame",
+                Undo => "button.undo",
+                Redo => "button.redo",
+                Help => "button.help",
+                NewGame => "button.new_game",
+                Settings => "button.settings",
+            }
+        }
+
+        /// Message key of the accessible name, see [super::accessibility].
+        pub fn label_key(&self) -> &'static str {
+            use Marker::*;
+
+            match self {
+                Undo => "a11y.undo",
+                Redo => "a11y.redo",
+                Help => "a11y.help",
+                NewGame => "a11y.new_game",
+                Settings => "a11y.settings",
             }
         }
     }
 }
 
 fn startup(
-    mut next_state: ResMut<NextState<InGame>>,
     mut timer: ResMut<Timer>,
     mut seed: ResMut<Seed>,
     mut tile_pos_variant_pairs: ResMut<TilePositionVariantPairs>,
@@ -1162,24 +785,33 @@ fn startup(
     *selected_tile = SelectedTile::default();
     *history = History::default();
     *help_enabled = HelpEnabled::default();
-    next_state.set(InGame::Init);
 }
 
-fn update_winit_settings(mut winit_settings: ResMut<WinitSettings>) {
-    winit_settings.focused_mode = UpdateMode::Continuous;
-    winit_settings.unfocused_mode = UpdateMode::Continuous;
+fn wait_for_theme(
+    theme: theme::Active,
+    mut next_state: ResMut<NextState<InGame>>,
+) {
+    if theme.is_loaded() {
+        next_state.set(InGame::Init);
+    }
 }
 
 fn spawn_background(
     mut commands: Commands,
     projection: Query<&Projection, With<Camera>>,
-    asset_server: Res<AssetServer>,
+    manifest: Res<Manifest>,
+    theme: theme::Active,
 ) {
     let Some(Projection::Orthographic(projection)) = projection.iter().next() else {
         panic!();
     };
 
-    let handle: Handle<Image> = asset_server.load("misc/rev2/original/Arthas_LichKing_GPT2.png");
+    let background = &theme.get().background;
+    let handle: Handle<Image> = background
+        .texture
+        .as_ref()
+        .map(|texture| manifest.image(texture))
+        .unwrap_or_default();
 
     spawn(
         &mut commands,
@@ -1187,7 +819,7 @@ fn spawn_background(
             marker::Background,
             Sprite {
                 custom_size: Some(Vec2::new(projection.area.width(), projection.area.height())),
-                color: Color::srgb(1.0, 1.0, 1.0).with_luminance(0.3),
+                color: background.color,
                 ..Sprite::from_image(handle)
             },
             Transform {
@@ -1204,278 +836,118 @@ fn spawn_background(
 fn bind_tiles_to_positions(
     mut tile_position_variant_pairs: ResMut<TilePositionVariantPairs>,
     platform: ResMut<Platform>,
+    rules: Res<rules::Rules>,
+    mut seed: ResMut<Seed>,
+    mut diagnostics: Diagnostics,
 ) {
-    let tile_grid_size = tile::PositionGenerator::<tile::Turtle>::TILE_GRID_SIZE as u32;
-    let position_generator =
-        tile::PositionGenerator::<tile::Turtle>::new(UVec2::splat(tile_grid_size));
-
-    let positions: Vec<tile::Position> = position_generator.collect();
-    let seed = platform.rng_seed_get();
-    let (mut positions, seed) = generate_solvable_board(positions, seed);
-    positions.reverse();
-    platform.rng_seed_set(seed);
+    let start = Instant::now();
+
+    let (positions, generated_seed) = match rules.mode() {
+        rules::Mode::ShisenSho => shisen::generate_board(platform.rng_seed_get()),
+        rules::Mode::Memory => memory::generate_board(platform.rng_seed_get()),
+        rules::Mode::Solitaire => {
+            let tile_grid_size = tile::PositionGenerator::<tile::Turtle>::TILE_GRID_SIZE as u32;
+            let position_generator =
+                tile::PositionGenerator::<tile::Turtle>::new(UVec2::splat(tile_grid_size));
+
+            let positions: Vec<tile::Position> = position_generator.collect();
+            let (mut positions, seed) =
+                generator::generate_solvable_board(positions, platform.rng_seed_get());
+            positions.reverse();
+            (positions, seed)
+        },
+    };
+
+    platform.rng_seed_set(generated_seed);
     tile_position_variant_pairs.0 = positions;
+    *seed = Seed(Some(generated_seed));
+    diagnostics.add_measurement(&GENERATION, || start.elapsed().as_secs_f64() * 1000.0);
 }
 
-/// Returns a [Vec] with (position, variant) tuples along with the rng seed ([u64]) to create them.
-fn generate_solvable_board(
-    mut available_positions: Vec<tile::Position>,
-    seed: Option<u64>,
-) -> (Vec<(tile::Position, tile::Variant)>, u64) {
-    if available_positions.len() % 2 != 0 {
-        panic!();
-    }
-
-    let mut result: Vec<(tile::Position, tile::Variant)> = Vec::new();
-    let mut occupied_positions: Vec<tile::Position> = Vec::with_capacity(available_positions.len());
-    let original_positions = available_positions.clone();
+/// See [TILES].
+fn measure_tiles(
+    mut diagnostics: Diagnostics,
+    tiles: Query<(), (With<tile::Marker<0>>, Without<marker::Hidden>)>,
+) {
+    diagnostics.add_measurement(&TILES, || tiles.iter().len() as f64);
+}
 
-    // Set rng seed
-    let seed = seed.unwrap_or(rand::random());
-    let mut rng = StdRng::seed_from_u64(seed);
+/// Tile which can be pressed, with what removing it changes.
+type PressedTile = (
+    Entity,
+    &'static tile::Variant,
+    &'static tile::Position,
+    &'static mut Visibility,
+    &'static players::Player,
+);
+
+/// State of the game which a press reads and changes.
+#[derive(SystemParam)]
+struct Play<'w> {
+    selected_tile: ResMut<'w, SelectedTile>,
+    history: ResMut<'w, History>,
+    rules: Res<'w, rules::Rules>,
+    index: Res<'w, board::BoardIndex>,
+    players: ResMut<'w, players::Players>,
+    next_state: ResMut<'w, NextState<InGame>>,
+}
 
-    // Generate [tile::Variant] pairs
-    let tile_variants: u32 = available_positions.len() as u32
-        / tile::PositionGenerator::<tile::Turtle>::TILE_VARIANT_GROUP_SIZE as u32;
-    let mut available_tile_variants: Vec<(tile::Variant, tile::Variant)> = Vec::new();
+fn tile_pressed(
+    on_press: On<Pointer<Press>>,
+    mut commands: Commands,
+    mut tiles: Query<PressedTile, InPlay>,
+    play: Play,
+    mut board_updated: MessageWriter<BoardUpdated>,
+    mut sfx: MessageWriter<Sfx>,
+    mut pair_removed: MessageWriter<PairRemoved>,
+) {
+    let Play {
+        mut selected_tile,
+        mut history,
+        rules,
+        index,
+        mut players,
+        mut next_state,
+    } = play;
+
+    let (pressed_entity, _, _, _, &player) =
+        tiles.iter().find(|tile| tile.0 == on_press.entity).unwrap();
+
+    let Some(selected_entity) = selected_tile[player.0].take() else {
+        selected_tile[player.0] = Some(pressed_entity);
+        sfx.write(Sfx::Click);
+        return;
+    };
 
-    for tile_variant in 0..tile_variants {
-        available_tile_variants.push((tile::Variant(tile_variant), tile::Variant(tile_variant)));
-        available_tile_variants.push((tile::Variant(tile_variant), tile::Variant(tile_variant)));
+    if selected_entity == pressed_entity {
+        sfx.write(Sfx::Click);
+        return;
     }
 
-    available_tile_variants.shuffle(&mut rng);
+    let [
+        (pressed_entity, pressed_variant, pressed_position, _pressed_visibility, _),
+        (selected_entity, selected_variant, selected_position, _selected_visibility, _),
+    ] = tiles.get_many([pressed_entity, selected_entity]).unwrap();
 
-    /// Returns [Option::Some] if the given index is a candidate to place next iteration.
-    fn valid_position_check<'a>(
-        index: usize,
-        available_positions: &Vec<tile::Position>,
-        occupied_positions: &Vec<tile::Position>,
-    ) -> Option<usize> {
-        let pos = &available_positions[index];
+    let board = index.get(player);
 
-        let mut overlapped_other_available_tile = false;
-        for other in available_positions.iter().enumerate() {
-            if other.0 == index {
-                continue;
-            }
+    if !valid_removal(
+        (pressed_entity, pressed_variant, pressed_position),
+        (selected_entity, selected_variant, selected_position),
+        board,
+        rules.get(),
+    ) {
+        selected_tile[player.0] = Some(pressed_entity);
+        sfx.write(Sfx::Mismatch);
+        return;
+    }
 
-            let is_on_same_layer = pos.z == other.1.z;
-            let is_above_other_tile = pos.z > other.1.z;
-            let is_overlapping_other_tile =
-                pos.x.abs_diff(other.1.x) < 2 && pos.y.abs_diff(other.1.y) < 2;
-
-            if is_on_same_layer && is_overlapping_other_tile {
-                panic!("Invalid/bad tile positioning!")
-            }
-
-            if is_above_other_tile && is_overlapping_other_tile {
-                overlapped_other_available_tile = true;
-                break;
-            }
-        }
-
-        if overlapped_other_available_tile {
-            debug!("INVALID: Obstructs other available tile position(s).");
-            debug!("{pos:?}");
-            return None;
-        }
-
-        let mut row_already_occupied = false;
-
-        for other in occupied_positions.iter().enumerate() {
-            let is_on_same_layer = pos.z == other.1.z;
-            let is_on_same_row = pos.y.abs_diff(other.1.y) < 2;
-
-            if is_on_same_layer && is_on_same_row {
-                row_already_occupied = true;
-                break;
-            }
-        }
-
-        if !row_already_occupied {
-            debug!("VALID: Row is not occupied by any other tile! Any position (column) is valid!");
-            debug!("{pos:?}");
-            return Some(index);
-        }
-
-        let mut is_next_to_occupied_tile = false;
-
-        for other in occupied_positions.iter().enumerate() {
-            let is_on_same_layer = pos.z == other.1.z;
-            let is_on_same_row = pos.y.abs_diff(other.1.y) < 2;
-            let is_next_to_other_tile = pos.x.abs_diff(other.1.x) == 2;
-
-            if is_on_same_layer && is_on_same_row && is_next_to_other_tile {
-                is_next_to_occupied_tile = true;
-                break;
-            }
-        }
-
-        if is_next_to_occupied_tile {
-            debug!("VALID: Tile (position) is next to an already occupied position");
-            debug!("{pos:?}");
-            return Some(index);
-        }
-
-        debug!("INVALID: Row is occupied, but tile is not next to it.");
-        debug!("{pos:?}");
-        None
-    }
-
-    for (v0, v1) in available_tile_variants {
-        debug!("\n\nNew pair placement!");
-
-        let v = [v0, v1];
-
-        // Find valid positions
-        let valid_positions = available_positions
-            .iter()
-            .enumerate()
-            .filter_map(|(index, _pos)| {
-                valid_position_check(index, &available_positions, &occupied_positions)
-            });
-
-        let mut valid: Vec<usize> = valid_positions.collect();
-        valid.shuffle(&mut rng);
-
-        let mut chosen_pair = None;
-
-        // Find a pair that remains valid after first placement
-        'outer: for &i in &valid {
-            for &j in &valid {
-                if i == j {
-                    continue;
-                }
-
-                // Simulate placing i
-                let mut available_tmp = available_positions.clone();
-                let mut occupied_tmp = occupied_positions.clone();
-
-                let pos_i = available_tmp.swap_remove(i);
-                occupied_tmp.push(pos_i);
-
-                // Recompute j index if needed
-                let j2 = if j > i { j - 1 } else { j };
-
-                if valid_position_check(j2, &available_tmp, &occupied_tmp).is_some() {
-                    chosen_pair = Some((i, j));
-                    break 'outer;
-                }
-            }
-        }
-
-        if chosen_pair.is_none() {
-            return generate_solvable_board(original_positions, Some(seed + 1));
-        }
-
-        let mut chosen_pair = vec![chosen_pair.unwrap().0, chosen_pair.unwrap().1];
-
-        if chosen_pair[1] == available_positions.len() - 1 {
-            // Since we are using swap remove, we have to adjust the second of the two indexes in this particular case.
-            chosen_pair[1] = chosen_pair[0];
-        }
-
-        for i in 0..2 {
-            result.push((available_positions[chosen_pair[i]], v[i]));
-            occupied_positions.push(available_positions.swap_remove(chosen_pair[i]));
-        }
-    }
-
-    if available_positions.len() > 0 {
-        panic!()
-    }
-
-    return (result, seed);
-}
-
-fn tile_pressed(
-    on_press: On<Pointer<Press>>,
-    mut commands: Commands,
-    mut tiles: Query<
-        (
-            Entity,
-            &tile::Variant,
-            &tile::Position,
-            &mut Sprite,
-            &mut Visibility,
-        ),
-        (With<tile::Marker<0>>, Without<marker::Hidden>),
-    >,
-    mut selected_tile: ResMut<SelectedTile>,
-    mut history: ResMut<History>,
-    mut board_updated: MessageWriter<BoardUpdated>,
-    mut next_state: ResMut<NextState<InGame>>,
-) {
-    let (pressed_entity, _, _, _, _) = tiles.iter().find(|tile| tile.0 == on_press.entity).unwrap();
-
-    let Some(selected_entity) = selected_tile.0.take() else {
-        let (_, _, _, mut pressed_sprite, _) = tiles.get_mut(pressed_entity).unwrap();
-        pressed_sprite.color = Color::hsl(0.5, 1.0, 1.5);
-        selected_tile.0 = Some(pressed_entity);
-        return;
-    };
-
-    if selected_entity == pressed_entity {
-        let (_, _, _, mut pressed_sprite, _) = tiles.get_mut(pressed_entity).unwrap();
-        pressed_sprite.color = tile::DEFAULT_COLOR;
-        return;
-    }
-
-    let (
-        _selected_entity,
-        _selected_variant,
-        _selected_position,
-        mut selected_sprite,
-        _selected_visibility,
-    ) = tiles.get_mut(selected_entity).unwrap();
-    selected_sprite.color = tile::DEFAULT_COLOR;
-
-    let [
-        (pressed_entity, pressed_variant, pressed_position, pressed_sprite, pressed_visibility),
-        (
-            selected_entity,
-            selected_variant,
-            selected_position,
-            _selected_sprite,
-            _selected_visibility,
-        ),
-    ] = tiles.get_many([pressed_entity, selected_entity]).unwrap();
-
-    if valid_removal(
-        pressed_entity,
-        selected_entity,
-        pressed_variant,
-        selected_variant,
-        pressed_position,
-        selected_position,
-        &tiles,
-    ) == false
-    {
-        let (_, _, _, mut pressed_sprite, _) = tiles.get_mut(pressed_entity).unwrap();
-        pressed_sprite.color = Color::hsl(0.5, 1.0, 1.5);
-        selected_tile.0 = Some(pressed_entity);
-        return;
-    }
-
-    let [
-        (
-            pressed_entity,
-            pressed_variant,
-            pressed_position,
-            mut pressed_sprite,
-            mut pressed_visibility,
-        ),
-        (
-            selected_entity,
-            selected_variant,
-            selected_position,
-            mut selected_sprite,
-            mut selected_visibility,
-        ),
-    ] = tiles
-        .get_many_mut([pressed_entity, selected_entity])
-        .unwrap();
+    let [
+        (pressed_entity, _pressed_variant, pressed_position, mut pressed_visibility, _),
+        (selected_entity, _selected_variant, selected_position, mut selected_visibility, _),
+    ] = tiles
+        .get_many_mut([pressed_entity, selected_entity])
+        .unwrap();
 
     history.push_front(HistoryItem::ValidPair(pressed_entity, selected_entity));
     commands.entity(pressed_entity).insert(marker::Hidden);
@@ -1484,37 +956,34 @@ fn tile_pressed(
     *selected_visibility = Visibility::Hidden;
 
     board_updated.write(BoardUpdated);
+    pair_removed.write(PairRemoved(player, *pressed_position, *selected_position));
+    sfx.write(Sfx::Match);
+    players.scored(player);
+    players.pass();
 
-    if tiles.iter().len() == 2 {
-        next_state.set(InGame::Victory);
+    if board.len() == 2 {
+        next_state.set(players.finish(player, true));
     }
 }
 
+/// Whether the `pressed` and `selected` tiles, each an entity with its variant and position, may be
+/// removed as a pair.
 fn valid_removal(
-    pressed_entity: Entity,
-    selected_entity: Entity,
-    pressed_variant: &tile::Variant,
-    selected_variant: &tile::Variant,
-    pressed_position: &tile::Position,
-    selected_position: &tile::Position,
-    tiles: &Query<
-        (
-            Entity,
-            &tile::Variant,
-            &tile::Position,
-            &mut Sprite,
-            &mut Visibility,
-        ),
-        (With<tile::Marker<0>>, Without<marker::Hidden>),
-    >,
+    pressed: (Entity, &tile::Variant, &tile::Position),
+    selected: (Entity, &tile::Variant, &tile::Position),
+    board: &board::Board,
+    rules: &dyn rules::RuleSet,
 ) -> bool {
+    let (pressed_entity, pressed_variant, pressed_position) = pressed;
+    let (selected_entity, selected_variant, selected_position) = selected;
+
     fn matching_variants(
         pressed_entity: Entity,
         selected_entity: Entity,
         pressed_variant: &tile::Variant,
         selected_variant: &tile::Variant,
     ) -> bool {
-        let m = pressed_entity != selected_entity && pressed_variant == selected_variant;
+        let m = pressed_entity != selected_entity && pressed_variant.matches(selected_variant);
 
         if !m {
             info!("Tiles are not matching!");
@@ -1523,208 +992,92 @@ fn valid_removal(
         m
     }
 
-    fn free_horizontally(
-        entity: Entity,
-        position: &tile::Position,
-        tiles: &Query<
-            (
-                Entity,
-                &tile::Variant,
-                &tile::Position,
-                &mut Sprite,
-                &mut Visibility,
-            ),
-            (With<tile::Marker<0>>, Without<marker::Hidden>),
-        >,
-    ) -> bool {
-        const TGS: u32 = tile::PositionGenerator::<tile::Turtle>::TILE_GRID_SIZE as u32;
-
-        let mut blocked_left = false;
-        let mut blocked_right = false;
-
-        for (other, _, other_pos, _, _) in tiles.iter() {
-            if other == entity {
-                continue;
-            }
-            if position.z != other_pos.z {
-                continue;
-            }
-
-            let overlapping_row = position.y + TGS > other_pos.y && position.y < other_pos.y + TGS;
-
-            if !overlapping_row {
-                continue;
-            }
-
-            if position.x == other_pos.x + TGS {
-                blocked_left = true;
-            }
-
-            if position.x + TGS == other_pos.x {
-                blocked_right = true;
-            }
-        }
-
-        !(blocked_left && blocked_right)
-    }
-
-    fn free_above(
-        entity: Entity,
-        position: &tile::Position,
-        tiles: &Query<
-            (
-                Entity,
-                &tile::Variant,
-                &tile::Position,
-                &mut Sprite,
-                &mut Visibility,
-            ),
-            (With<tile::Marker<0>>, Without<marker::Hidden>),
-        >,
-    ) -> bool {
-        const TGS: u32 = tile::PositionGenerator::<tile::Turtle>::TILE_GRID_SIZE as u32;
-
-        !tiles.iter().any(|(other, _, other_pos, _, _)| {
-            other != entity
-                && other_pos.z > position.z
-                && position.y + TGS > other_pos.y
-                && position.y < other_pos.y + TGS
-                && position.x + TGS > other_pos.x
-                && position.x < other_pos.x + TGS
-        })
-    }
-
     matching_variants(
         pressed_entity,
         selected_entity,
         pressed_variant,
         selected_variant,
-    ) && free_horizontally(pressed_entity, pressed_position, tiles)
-        && free_horizontally(selected_entity, selected_position, tiles)
-        && free_above(pressed_entity, pressed_position, tiles)
-        && free_above(selected_entity, selected_position, tiles)
+    ) && rules.valid_removal(
+        (pressed_variant, pressed_position),
+        (selected_variant, selected_position),
+        board,
+    )
 }
 
 fn spawn_buttons(
     mut commands: Commands,
-    projection: Query<&Projection, With<Camera>>,
-    asset_server: Res<AssetServer>,
+    anchors: Query<(Entity, &hud::Anchor)>,
+    manifest: Res<Manifest>,
+    images: Res<Assets<Image>>,
     mut texture_atlases: ResMut<Assets<TextureAtlasLayout>>,
 ) {
-    let Some(Projection::Orthographic(projection)) = projection.iter().next() else {
-        panic!();
-    };
-
-    let texture_handle: Handle<Image> = asset_server.load(button::asset::BUTTON);
-    let texture_atlas =
-        TextureAtlasLayout::from_grid(UVec2::new(666 / 3, 429 / 3), 3, 1, None, None);
+    let texture_handle = manifest.button.clone();
+    // Normal, hovered and pressed side by side, at whatever size the atlas was processed to. Only
+    // missing if it failed to load, in which case there is nothing to cut up anyway.
+    let size = images
+        .get(&texture_handle)
+        .map_or(UVec2::new(3, 1), Image::size);
+    let texture_atlas = TextureAtlasLayout::from_grid(size / UVec2::new(3, 1), 3, 1, None, None);
     let texture_atlas_handle = texture_atlases.add(texture_atlas);
 
-    let button_size = Vec2::new(
-        (projection.area.height() / tile::PositionGenerator::<tile::Turtle>::ROWS as f32) / 0.7,
-        projection.area.height() / tile::PositionGenerator::<tile::Turtle>::ROWS as f32,
-    );
-    let font = (
-        TextFont {
-            font_size: button_size.y / 5.0,
-            ..default()
-        },
-        Text2dShadow {
-            offset: Vec2 { x: 3.0, y: -3.0 },
-            color: Color::srgba(0.0, 0.0, 0.0, 0.95),
-        },
-        TextColor(Color::srgb_u8(239, 191, 4)),
-    );
+    let button_size = hud::element_size();
+    let font = hud::font(button_size.y / 5.0);
 
     struct Button {
         marker: button::Marker,
-        flip_x: bool,
-        offset: Vec3,
+        anchor: hud::Anchor,
     }
 
     let buttons = [
         Button {
             marker: button::Marker::Undo,
-            flip_x: false,
-            offset: Vec3::default(),
+            anchor: hud::Anchor::BottomLeft,
         },
         Button {
             marker: button::Marker::Redo,
-            flip_x: false,
-            offset: Vec3 {
-                y: button_size.y,
-                ..default()
-            },
+            anchor: hud::Anchor::BottomLeft,
         },
         Button {
             marker: button::Marker::NewGame,
-            flip_x: true,
-            offset: Vec3::default(),
+            anchor: hud::Anchor::BottomRight,
         },
         Button {
             marker: button::Marker::Help,
-            flip_x: true,
-            offset: Vec3 {
-                y: button_size.y,
-                ..default()
-            },
+            anchor: hud::Anchor::BottomRight,
+        },
+        Button {
+            marker: button::Marker::Settings,
+            anchor: hud::Anchor::TopRight,
         },
     ];
 
     for button in buttons {
-        let mut ec = spawn(
-            &mut commands,
-            (
-                button.marker.clone(),
-                button::ResizeData(button.offset.truncate(), button.flip_x),
-                Sprite {
-                    custom_size: Some(button_size),
-                    ..Sprite::from_atlas_image(
-                        texture_handle.clone(),
-                        TextureAtlas {
-                            layout: texture_atlas_handle.clone(),
-                            index: 0,
-                        },
-                    )
-                },
-                Transform {
-                    translation: Vec3 {
-                        x: (-projection.area.width() / 2.0)
-                            * if button.flip_x { -1.0 } else { 1.0 },
-                        y: -projection.area.height() / 2.0,
-                        ..default()
-                    } + button.offset,
-                    ..default()
-                },
-                if button.flip_x {
-                    Anchor::BOTTOM_RIGHT
-                } else {
-                    Anchor::BOTTOM_LEFT
+        let mut ec = commands.spawn((
+            ChildOf(hud::anchor_entity(&anchors, button.anchor)),
+            button.marker.clone(),
+            bevy::prelude::Button,
+            Node {
+                width: Val::Px(button_size.x),
+                height: Val::Px(button_size.y),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            ImageNode::from_atlas_image(
+                texture_handle.clone(),
+                TextureAtlas {
+                    layout: texture_atlas_handle.clone(),
+                    index: 0,
                 },
-                children![(
-                    button.marker.clone(),
-                    Text2d(button.marker.as_string().to_owned()),
-                    font.clone(),
-                    Transform {
-                        translation: button_size.extend(0.0) / 2.0
-                            * if button.flip_x {
-                                Vec3 {
-                                    x: -1.0,
-                                    y: 1.0,
-                                    z: 1.0,
-                                }
-                            } else {
-                                Vec3 {
-                                    x: 1.0,
-                                    y: 1.0,
-                                    z: 1.0,
-                                }
-                            },
-                        ..default()
-                    },
-                )],
             ),
-        );
+            children![(
+                button.marker.clone(),
+                Localized(button.marker.key()),
+                Text::default(),
+                font.clone(),
+                Pickable::IGNORE,
+            )],
+        ));
 
         ec.observe(mouse_over)
             .observe(mouse_out)
@@ -1744,128 +1097,111 @@ fn spawn_buttons(
             button::Marker::NewGame => {
                 ec.observe(new_game_mouse);
             },
+            button::Marker::Settings => {
+                ec.observe(settings_mouse);
+            },
         };
     }
 }
 
 fn spawn_info(
     mut commands: Commands,
-    projection: Query<&Projection, With<Camera>>,
-    asset_server: Res<AssetServer>,
+    anchors: Query<(Entity, &hud::Anchor)>,
+    manifest: Res<Manifest>,
+    rules: Res<rules::Rules>,
+    players: Res<players::Players>,
 ) {
-    let Some(Projection::Orthographic(projection)) = projection.iter().next() else {
-        panic!();
-    };
+    let texture_handle = manifest.info.clone();
 
-    let texture_handle: Handle<Image> = asset_server.load(info::asset::INFO);
-
-    let info_size = Vec2::new(
-        (projection.area.height() / tile::PositionGenerator::<tile::Turtle>::ROWS as f32) / 0.7,
-        projection.area.height() / tile::PositionGenerator::<tile::Turtle>::ROWS as f32,
-    );
-    let font = (
-        TextFont {
-            font_size: info_size.y / 5.0,
-            ..default()
-        },
-        Text2dShadow {
-            offset: Vec2 { x: 3.0, y: -3.0 },
-            color: Color::srgba(0.0, 0.0, 0.0, 0.95),
-        },
-        TextColor(Color::srgb_u8(239, 191, 4)),
-    );
+    let info_size = hud::element_size();
+    let font = hud::font(info_size.y / 5.0);
 
     struct Info {
         marker: info::Marker,
-        flip_x: bool,
-        offset: Vec3,
+        anchor: hud::Anchor,
     }
 
-    let infos = [Info {
-        marker: info::Marker::Moves,
-        flip_x: false,
-        offset: Vec3 {
-            y: info_size.y * 2.0,
-            ..default()
-        },
-    }];
+    let mut infos = Vec::new();
+
+    if players.multiplayer() == players::Multiplayer::Versus {
+        // Each player's info above their own board.
+        for (player, anchor) in [(0, hud::Anchor::TopLeft), (1, hud::Anchor::TopRight)] {
+            infos.push(Info {
+                marker: info::Marker::Score(player),
+                anchor,
+            });
+            infos.push(Info {
+                marker: info::Marker::PlayerMoves(player),
+                anchor,
+            });
+        }
+    } else {
+        infos.push(Info {
+            marker: info::Marker::Moves,
+            anchor: hud::Anchor::BottomLeft,
+        });
+    }
+
+    if players.multiplayer() == players::Multiplayer::HotSeat {
+        for (player, anchor) in [(0, hud::Anchor::TopLeft), (1, hud::Anchor::TopRight)] {
+            infos.push(Info {
+                marker: info::Marker::Score(player),
+                anchor,
+            });
+        }
+    }
+
+    if rules.get().time_limit().is_some() {
+        infos.push(Info {
+            marker: info::Marker::Time,
+            anchor: hud::Anchor::TopLeft,
+        });
+    }
+
+    if rules.get().timed() {
+        infos.push(Info {
+            marker: info::Marker::Elapsed,
+            anchor: hud::Anchor::TopLeft,
+        });
+    }
+
+    if rules.get().undo_limit().is_some() {
+        infos.push(Info {
+            marker: info::Marker::Undos,
+            anchor: hud::Anchor::TopLeft,
+        });
+    }
 
     for info in infos {
-        spawn(
-            &mut commands,
-            (
+        commands.spawn((
+            ChildOf(hud::anchor_entity(&anchors, info.anchor)),
+            info.marker.clone(),
+            Node {
+                width: Val::Px(info_size.x),
+                height: Val::Px(info_size.y),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            ImageNode {
+                color: info::SLAB_COLOR,
+                ..ImageNode::new(texture_handle.clone())
+            },
+            children![(
                 info.marker.clone(),
-                info::ResizeData(info.offset.truncate(), info.flip_x),
-                Sprite {
-                    custom_size: Some(info_size),
-                    color: Color::hsl(0.0, 0.0, 0.7),
-                    ..Sprite::from_image(texture_handle.clone())
-                },
-                Transform {
-                    translation: Vec3 {
-                        x: (-projection.area.width() / 2.0) * if info.flip_x { -1.0 } else { 1.0 },
-                        y: -projection.area.height() / 2.0,
-                        ..default()
-                    } + info.offset,
-                    ..default()
-                },
-                if info.flip_x {
-                    Anchor::BOTTOM_RIGHT
-                } else {
-                    Anchor::BOTTOM_LEFT
-                },
-                children![(
-                    info.marker.clone(),
-                    Text2d(info.marker.as_string().to_owned()),
-                    font.clone(),
-                    Transform {
-                        translation: info_size.extend(0.0) / 2.0
-                            * if info.flip_x {
-                                Vec3 {
-                                    x: -1.0,
-                                    y: 1.0,
-                                    z: 1.0,
-                                }
-                            } else {
-                                Vec3 {
-                                    x: 1.0,
-                                    y: 1.0,
-                                    z: 1.0,
-                                }
-                            },
-                        ..default()
-                    },
-                )],
-            ),
-        );
+                Localized(info.marker.key()),
+                Text::default(),
+                TextLayout::new_with_justify(Justify::Center),
+                font.clone(),
+                Pickable::IGNORE,
+                children![(info.marker.clone(), TextSpan::default(), font.clone())],
+            )],
+        ));
     }
 }
 
 fn resize(
-    mut transform: Query<
-        (&mut Transform, &mut Sprite),
-        (
-            With<marker::Background>,
-            Without<button::Marker>,
-            Without<info::Marker>,
-        ),
-    >,
-    buttons: Query<
-        (&mut Transform, &button::ResizeData),
-        (
-            With<button::Marker>,
-            Without<marker::Background>,
-            Without<info::Marker>,
-        ),
-    >,
-    infos: Query<
-        (&mut Transform, &info::ResizeData),
-        (
-            With<info::Marker>,
-            Without<marker::Background>,
-            Without<button::Marker>,
-        ),
-    >,
+    mut transform: Query<(&mut Transform, &mut Sprite), With<marker::Background>>,
     projection: Query<&Projection, With<Camera>>,
 ) {
     let Some(Projection::Orthographic(projection)) = projection.iter().next() else {
@@ -1884,166 +1220,205 @@ fn resize(
             y: projection.area.height(),
         });
     }
+}
 
-    for (mut button_transform, button_resize_data) in buttons {
-        button_transform.translation = Vec3 {
-            x: (-projection.area.width() / 2.0) * if button_resize_data.1 { -1.0 } else { 1.0 },
-            y: -projection.area.height() / 2.0,
-            ..default()
-        } + button_resize_data.0.extend(0.0);
-    }
-
-    for (mut info_transform, info_resize_data) in infos {
-        info_transform.translation = Vec3 {
-            x: (-projection.area.width() / 2.0) * if info_resize_data.1 { -1.0 } else { 1.0 },
-            y: -projection.area.height() / 2.0,
-            ..default()
-        } + info_resize_data.0.extend(0.0);
-    }
+/// What tiles are drawn with.
+#[derive(SystemParam)]
+struct TileArt<'w> {
+    manifest: Res<'w, Manifest>,
+    faces: Res<'w, atlas::FaceAtlas>,
+    theme: theme::Active<'w>,
 }
 
 fn spawn_tiles(
     mut commands: Commands,
     projection: Query<&Projection, With<Camera>>,
-    asset_server: Res<AssetServer>,
+    art: TileArt,
     tile_position_variant_pairs: ResMut<TilePositionVariantPairs>,
     mut board_updated: MessageWriter<BoardUpdated>,
+    rules: Res<rules::Rules>,
+    players: Res<players::Players>,
 ) {
     let Some(Projection::Orthographic(projection)) = projection.iter().next() else {
         panic!();
     };
 
-    let tile_texture: Handle<Image> = asset_server.load(tile::asset::texture::TILE);
-    let tile_size = Vec2::new(
-        (projection.area.height() / tile::PositionGenerator::<tile::Turtle>::ROWS as f32) * 0.85,
-        projection.area.height() / tile::PositionGenerator::<tile::Turtle>::ROWS as f32,
-    );
-    let tile_grid_size = tile::PositionGenerator::<tile::Turtle>::TILE_GRID_SIZE as u32;
-    let tile_size_full = Vec2::new(
-        (tile_size.x / tile::asset::texture::TILE_NO_BORDER_WIDTH as f32)
-            * tile::asset::texture::TILE_WIDTH as f32,
-        (tile_size.y / tile::asset::texture::TILE_NO_BORDER_HEIGHT as f32)
-            * tile::asset::texture::TILE_HEIGHT as f32,
-    );
-    let tile_size_ratio = tile_size.y / tile::asset::texture::TILE_NO_BORDER_HEIGHT as f32;
-    let tile_border_length_scaled =
-        tile::asset::texture::TILE_BORDER_LENGTH as f32 * tile_size_ratio;
-    let tile_pos_offset = Vec3::new(
-        -(tile_size.x * tile::PositionGenerator::<tile::Turtle>::COLUMNS as f32 / 2.0)
-            + tile_size.x * 1.0
-            - tile_border_length_scaled / 2.0,
-        -projection.area.height() / 2.0 + tile_size_full.y * 0.5 - tile_border_length_scaled,
-        0.0,
-    );
+    let TileArt {
+        manifest,
+        faces,
+        theme,
+    } = art;
+    let theme = theme.get();
+    let tile_texture = manifest.image(&theme.tile.texture);
+    let geometry = tile::Geometry::new(projection, &theme.tile);
+
+    // Every player gets the same deal.
+    for player in players.boards() {
+        for (pos, variant) in tile_position_variant_pairs.iter() {
+            let mut entity_commands = spawn(
+                &mut commands,
+                (
+                    Visibility::Hidden,
+                    marker::Hidden,
+                    tile::Tile {
+                        marker: tile::Marker::<0>,
+                        position: *pos,
+                        variant: *variant,
+                    },
+                    Sprite {
+                        custom_size: Some(geometry.size_full),
+                        color: theme.tile.color,
+                        ..Sprite::from_image(tile_texture.clone())
+                    },
+                    Transform {
+                        translation: geometry.translation(pos),
+                        ..default()
+                    },
+                    accessibility::tile(),
+                    player,
+                    Propagate(player.render_layers()),
+                ),
+            );
 
-    let default_depth = Vec3::default().with_z(100.0);
-    let column_depth_offset_factor = Vec3::default().with_z(-0.1);
-    let row_depth_offset_factor =
-        column_depth_offset_factor * tile::PositionGenerator::<tile::Turtle>::COLUMNS as f32;
-    let layer_depth_offset_factor = Vec3::default().with_z(10.0);
-    let layer_offset_factor = Vec3 {
-        x: tile_border_length_scaled,
-        y: tile_border_length_scaled,
-        ..default()
-    };
+            if rules.mode() == rules::Mode::Memory {
+                entity_commands.observe(memory::tile_pressed);
+            } else {
+                entity_commands.observe(tile_pressed);
+            }
 
-    for (pos, variant) in tile_position_variant_pairs.iter() {
-        let special = match pos.x / tile_grid_size {
-            0 => Vec3::default().with_z(
-                -column_depth_offset_factor.z
-                    * tile::PositionGenerator::<tile::Turtle>::COLUMNS as f32,
-            ),
-            13 | 14 => Vec3::default().with_z(
-                column_depth_offset_factor.z
-                    * (tile::PositionGenerator::<tile::Turtle>::COLUMNS as f32),
-            ),
-            _ => Vec3::default(),
-        };
+            if pos.z != 0 {
+                entity_commands.with_child(geometry.shadow(
+                    pos,
+                    tile_texture.clone(),
+                    theme.tile.shadow_color,
+                ));
+            }
 
-        let mut entity_commands = spawn(
-            &mut commands,
-            (
-                Visibility::Hidden,
-                marker::Hidden,
-                tile::Tile {
-                    marker: tile::Marker::<0>,
-                    position: *pos,
-                    variant: *variant,
-                },
-                Sprite {
-                    custom_size: Some(tile_size_full),
-                    color: tile::DEFAULT_COLOR,
-                    ..Sprite::from_image(tile_texture.clone())
-                },
-                Transform {
-                    translation: (((pos.as_vec3() / tile_grid_size as f32)
-                        * tile_size.extend(1.0))
-                        + tile_pos_offset)
-                        + default_depth
-                        + (layer_offset_factor * pos.z as f32)
-                        + (column_depth_offset_factor * pos.x as f32)
-                        + (row_depth_offset_factor * pos.y as f32)
-                        + (layer_depth_offset_factor * pos.z as f32)
-                        + special,
-                    ..default()
-                },
-            ),
-        );
+            tile::Variant::insert_sprite_as_child(
+                &faces,
+                theme,
+                &mut entity_commands,
+                variant.0,
+                &geometry.size,
+                &geometry.face_offset(),
+            );
+        }
+    }
 
-        entity_commands.observe(tile_pressed);
+    board_updated.write(BoardUpdated);
+}
 
-        if pos.z != 0 {
-            entity_commands.with_child((
-                Sprite {
-                    custom_size: Some(tile_size_full),
-                    color: Color::hsla(0.0, 0.0, 0.0, 0.75),
-                    ..Sprite::from_image(tile_texture.clone())
-                },
-                Transform {
-                    scale: Vec3 {
-                        x: 1.2,
-                        y: 1.03,
-                        ..Vec3::splat(1.0)
-                    },
-                    translation: Vec3 {
-                        x: -tile_size_full.x / 2.0,
-                        y: -tile_size_full.y / 2.0,
-                        z: column_depth_offset_factor.z * pos.x as f32,
-                        ..default()
-                    },
-                    ..default()
-                },
-                Anchor::BOTTOM_LEFT,
-            ));
+/// Tile with the parts of it that [apply_theme] changes.
+type ThemedTile = (
+    Entity,
+    &'static tile::Position,
+    &'static tile::Variant,
+    &'static mut Transform,
+    &'static mut Sprite,
+    &'static Children,
+);
+type ShadowSprite = (&'static mut Transform, &'static mut Sprite);
+type BackgroundFilter = (
+    With<marker::Background>,
+    Without<tile::Marker<0>>,
+    Without<tile::Shadow>,
+);
+
+/// Everything on the board which is drawn as the active theme says.
+#[derive(SystemParam)]
+struct Themed<'w, 's> {
+    tiles: Query<'w, 's, ThemedTile, (With<tile::Marker<0>>, Without<tile::Shadow>)>,
+    shadows: Query<'w, 's, ShadowSprite, (With<tile::Shadow>, Without<tile::Marker<0>>)>,
+    faces: Query<'w, 's, (), With<tile::Face>>,
+    background: Query<'w, 's, &'static mut Sprite, BackgroundFilter>,
+}
+
+/// Re-themes the board in place whenever the active theme changes (or is modified on disk, which
+/// bakes its faces anew).
+fn apply_theme(
+    mut commands: Commands,
+    theme: theme::Active,
+    mut applied: Local<Option<AssetId<theme::Theme>>>,
+    projection: Query<&Projection, With<Camera>>,
+    manifest: Res<Manifest>,
+    face_atlas: Res<atlas::FaceAtlas>,
+    themed: Themed,
+) {
+    if !theme.is_loaded() || (*applied == Some(theme.id()) && !face_atlas.is_changed()) {
+        return;
+    }
+    *applied = Some(theme.id());
+
+    let Some(Projection::Orthographic(projection)) = projection.iter().next() else {
+        panic!();
+    };
+
+    let theme = theme.get();
+    info!("Applying theme \"{}\"", theme.name);
+
+    let Themed {
+        mut tiles,
+        mut shadows,
+        faces,
+        mut background,
+    } = themed;
+
+    let tile_texture = manifest.image(&theme.tile.texture);
+    let geometry = tile::Geometry::new(projection, &theme.tile);
+
+    // Tile colours are left to [highlight::apply], which also reacts to theme changes.
+    for (entity, pos, variant, mut transform, mut sprite, children) in &mut tiles {
+        transform.translation = geometry.translation(pos);
+        sprite.image = tile_texture.clone();
+        sprite.custom_size = Some(geometry.size_full);
+
+        for child in children {
+            if let Ok((mut shadow_transform, mut shadow_sprite)) = shadows.get_mut(*child) {
+                *shadow_transform = geometry.shadow_transform(pos);
+                shadow_sprite.image = tile_texture.clone();
+                shadow_sprite.custom_size = Some(geometry.size_full);
+                shadow_sprite.color = theme.tile.shadow_color;
+            } else if faces.contains(*child) {
+                commands.entity(*child).despawn();
+            }
         }
 
-        let offset = layer_offset_factor / 2.0;
         tile::Variant::insert_sprite_as_child(
-            &asset_server,
-            &mut entity_commands,
+            &face_atlas,
+            theme,
+            &mut commands.entity(entity),
             variant.0,
-            &tile_size,
-            &offset,
+            &geometry.size,
+            &geometry.face_offset(),
         );
     }
 
-    board_updated.write(BoardUpdated);
+    for mut sprite in &mut background {
+        sprite.image = theme
+            .background
+            .texture
+            .as_ref()
+            .map(|texture| manifest.image(texture))
+            .unwrap_or_default();
+        sprite.color = theme.background.color;
+    }
 }
 
 fn progressively_show_tiles(
     mut commands: Commands,
-    mut tiles: Query<(Entity, &mut Visibility), (With<tile::Marker<0>>, With<marker::Hidden>)>,
-    default_winit_settings: ResMut<DefaultWinitSettings>,
-    mut winit_settings: ResMut<WinitSettings>,
+    mut tiles: Query<(Entity, &mut Visibility), Removed>,
     mut next_state: ResMut<NextState<InGame>>,
     mut board_updated: MessageWriter<BoardUpdated>,
+    mut frame_budget: ResMut<FrameBudget>,
 ) {
     if tiles.iter().len() == 0 {
-        *winit_settings = default_winit_settings.0.clone();
         board_updated.write(BoardUpdated);
         next_state.set(InGame::Running);
+        return;
     }
 
+    frame_budget.request("deal");
+
     for (index, (entity, mut visibility)) in tiles.iter_mut().enumerate() {
         commands.entity(entity).remove::<marker::Hidden>();
         *visibility = Visibility::Inherited;
@@ -2056,171 +1431,161 @@ fn progressively_show_tiles(
 
 fn mouse_activity(
     entity: Entity,
-    buttons: &mut Query<(Entity, &mut Sprite)>,
+    buttons: &mut Query<(Entity, &mut ImageNode)>,
     new_index: usize,
 ) {
-    let (_entity, mut sprite) = buttons
+    let (_entity, mut image) = buttons
         .iter_mut()
         .find(|(entity_, _)| *entity_ == entity)
         .unwrap();
-    sprite.texture_atlas.as_mut().unwrap().index = new_index;
+    image.texture_atlas.as_mut().unwrap().index = new_index;
 }
 
 fn mouse_over(
     on_over: On<Pointer<Over>>,
-    mut buttons: Query<(Entity, &mut Sprite)>,
+    mut buttons: Query<(Entity, &mut ImageNode)>,
 ) {
     mouse_activity(on_over.entity, &mut buttons, 1);
 }
 
 fn mouse_out(
     on_out: On<Pointer<Out>>,
-    mut buttons: Query<(Entity, &mut Sprite)>,
+    mut buttons: Query<(Entity, &mut ImageNode)>,
 ) {
     mouse_activity(on_out.entity, &mut buttons, 0);
 }
 
 fn mouse_press(
     on_press: On<Pointer<Press>>,
-    mut buttons: Query<(Entity, &mut Sprite)>,
+    mut buttons: Query<(Entity, &mut ImageNode)>,
+    mut sfx: MessageWriter<Sfx>,
 ) {
     mouse_activity(on_press.entity, &mut buttons, 2);
+    sfx.write(Sfx::Click);
 }
 
 fn mouse_release(
     on_release: On<Pointer<Release>>,
-    mut buttons: Query<(Entity, &mut Sprite)>,
+    mut buttons: Query<(Entity, &mut ImageNode)>,
 ) {
     mouse_activity(on_release.entity, &mut buttons, 1);
 }
 
+/// What undoing a removal reads and changes, see [Undo::run].
+#[derive(SystemParam)]
+struct Undo<'w, 's> {
+    commands: Commands<'w, 's>,
+    removed_tiles: Query<'w, 's, &'static mut Visibility, Removed>,
+    history: ResMut<'w, History>,
+    board_updated: MessageWriter<'w, BoardUpdated>,
+    selected_tile: ResMut<'w, SelectedTile>,
+    sfx: MessageWriter<'w, Sfx>,
+    rules: Res<'w, rules::Rules>,
+    progress: ResMut<'w, rules::Progress>,
+    players: Res<'w, players::Players>,
+}
+
+impl Undo<'_, '_> {
+    fn run(&mut self) {
+        self.undo();
+        self.board_updated.write(BoardUpdated);
+    }
+
+    fn undo(&mut self) {
+        if !self.players.allows_undo() {
+            info!("No undos in multiplayer games!");
+            self.sfx.write(Sfx::Mismatch);
+            return;
+        }
+
+        if !self.progress.can_undo(self.rules.get()) {
+            info!("No undos left!");
+            self.sfx.write(Sfx::Mismatch);
+            return;
+        }
+
+        if let Some(HistoryItem::ValidPair(entity0, entity1)) = self.history.pop_front() {
+            *self.selected_tile = SelectedTile::default();
+            self.progress.undos += 1;
+            self.sfx.write(Sfx::Undo);
+
+            let [mut a, mut b] = self.removed_tiles.get_many_mut([entity0, entity1]).unwrap();
+            self.commands.entity(entity0).remove::<marker::Hidden>();
+            self.commands.entity(entity1).remove::<marker::Hidden>();
+            *a = Visibility::Inherited;
+            *b = Visibility::Inherited;
+        }
+    }
+}
+
 fn undo_mouse(
     _on_press: On<Pointer<Press>>,
-    mut commands: Commands,
-    mut history_valid_pair_tiles: Query<
-        &mut Visibility,
-        (With<tile::Marker<0>>, With<marker::Hidden>),
-    >,
-    mut history: ResMut<History>,
-    mut board_updated: MessageWriter<BoardUpdated>,
-    mut selected_tile: ResMut<SelectedTile>,
+    mut undo: Undo,
 ) {
-    undo(
-        &mut commands,
-        &mut history_valid_pair_tiles,
-        &mut history,
-        &mut selected_tile,
-    );
-    board_updated.write(BoardUpdated);
+    undo.run();
 }
 
 fn undo_keyboard(
     key: Res<ButtonInput<KeyCode>>,
-    mut commands: Commands,
-    mut history_valid_pair_tiles: Query<
-        &mut Visibility,
-        (With<tile::Marker<0>>, With<marker::Hidden>),
-    >,
-    mut history: ResMut<History>,
-    mut board_updated: MessageWriter<BoardUpdated>,
-    mut selected_tile: ResMut<SelectedTile>,
+    mut undo: Undo,
 ) {
     if key.just_pressed(KeyCode::KeyU) {
-        undo(
-            &mut commands,
-            &mut history_valid_pair_tiles,
-            &mut history,
-            &mut selected_tile,
-        );
-        board_updated.write(BoardUpdated);
+        undo.run();
     }
 }
 
-fn undo(
-    commands: &mut Commands,
-    history_valid_pair_tiles: &mut Query<
-        &mut Visibility,
-        (With<tile::Marker<0>>, With<marker::Hidden>),
-    >,
-    history: &mut ResMut<History>,
-    selected_tile: &mut ResMut<SelectedTile>,
-) {
-    if let Some(history_item) = history.pop_front() {
-        ***selected_tile = None;
-
-        match history_item {
-            HistoryItem::ValidPair(entity0, entity1) => {
-                let [mut a, mut b] = history_valid_pair_tiles
-                    .get_many_mut([entity0, entity1])
-                    .unwrap();
-                commands.entity(entity0).remove::<marker::Hidden>();
-                commands.entity(entity1).remove::<marker::Hidden>();
-                *a = Visibility::Inherited;
-                *b = Visibility::Inherited;
-            },
-            HistoryItem::Shuffle(items) => todo!(),
+/// What redoing an undone removal reads and changes, see [Redo::run].
+#[derive(SystemParam)]
+struct Redo<'w, 's> {
+    commands: Commands<'w, 's>,
+    tiles: Query<'w, 's, &'static mut Visibility, With<tile::Marker<0>>>,
+    history: ResMut<'w, History>,
+    board_updated: MessageWriter<'w, BoardUpdated>,
+    selected_tile: ResMut<'w, SelectedTile>,
+    sfx: MessageWriter<'w, Sfx>,
+    players: Res<'w, players::Players>,
+}
+
+impl Redo<'_, '_> {
+    fn run(&mut self) {
+        self.redo();
+        self.board_updated.write(BoardUpdated);
+    }
+
+    fn redo(&mut self) {
+        if !self.players.allows_undo() {
+            self.sfx.write(Sfx::Mismatch);
+            return;
+        }
+
+        if let Some(HistoryItem::ValidPair(entity0, entity1)) = self.history.pop_front_redo() {
+            *self.selected_tile = SelectedTile::default();
+            self.sfx.write(Sfx::Match);
+
+            let [mut a, mut b] = self.tiles.get_many_mut([entity0, entity1]).unwrap();
+            self.history
+                .push_front_redo(HistoryItem::ValidPair(entity0, entity1));
+            self.commands.entity(entity0).insert(marker::Hidden);
+            self.commands.entity(entity1).insert(marker::Hidden);
+            *a = Visibility::Hidden;
+            *b = Visibility::Hidden;
         }
     }
 }
 
 fn redo_mouse(
     _on_press: On<Pointer<Press>>,
-    mut commands: Commands,
-    mut history_valid_pair_tiles: Query<&mut Visibility, With<tile::Marker<0>>>,
-    mut history: ResMut<History>,
-    mut board_updated: MessageWriter<BoardUpdated>,
-    mut selected_tile: ResMut<SelectedTile>,
+    mut redo: Redo,
 ) {
-    redo(
-        &mut commands,
-        &mut history_valid_pair_tiles,
-        &mut history,
-        &mut selected_tile,
-    );
-    board_updated.write(BoardUpdated);
+    redo.run();
 }
 
 fn redo_keyboard(
     key: Res<ButtonInput<KeyCode>>,
-    mut commands: Commands,
-    mut history_valid_pair_tiles: Query<&mut Visibility, With<tile::Marker<0>>>,
-    mut history: ResMut<History>,
-    mut board_updated: MessageWriter<BoardUpdated>,
-    mut selected_tile: ResMut<SelectedTile>,
+    mut redo: Redo,
 ) {
     if key.just_pressed(KeyCode::KeyR) {
-        redo(
-            &mut commands,
-            &mut history_valid_pair_tiles,
-            &mut history,
-            &mut selected_tile,
-        );
-        board_updated.write(BoardUpdated);
-    }
-}
-
-fn redo(
-    commands: &mut Commands,
-    history_valid_pair_tiles: &mut Query<&mut Visibility, With<tile::Marker<0>>>,
-    history: &mut ResMut<History>,
-    selected_tile: &mut ResMut<SelectedTile>,
-) {
-    if let Some(history_item) = history.pop_front_redo() {
-        ***selected_tile = None;
-
-        match history_item {
-            HistoryItem::ValidPair(entity0, entity1) => {
-                let [mut a, mut b] = history_valid_pair_tiles
-                    .get_many_mut([entity0, entity1])
-                    .unwrap();
-                history.push_front_redo(HistoryItem::ValidPair(entity0, entity1));
-                commands.entity(entity0).insert(marker::Hidden);
-                commands.entity(entity1).insert(marker::Hidden);
-                *a = Visibility::Hidden;
-                *b = Visibility::Hidden;
-            },
-            HistoryItem::Shuffle(items) => todo!(),
-        }
+        redo.run();
     }
 }
 
@@ -2242,8 +1607,9 @@ fn help_keyboard(
 
 fn help_toggle(
     mut help_msg: MessageReader<HelpMsg>,
-    mut buttons: Query<(Entity, &button::Marker, &mut Sprite)>,
+    mut buttons: Query<(Entity, &button::Marker, &mut ImageNode)>,
     mut help_enabled: ResMut<HelpEnabled>,
+    palette: Res<highlight::Palette>,
 ) {
     if help_msg.is_empty() {
         return;
@@ -2252,182 +1618,93 @@ fn help_toggle(
 
     **help_enabled = !(**help_enabled);
 
-    let (_, _, mut button_sprite) = buttons
+    let (_, _, mut button_image) = buttons
         .iter_mut()
-        .find(|(_entity, marker, _sprite)| **marker == button::Marker::Help)
+        .find(|(_entity, marker, _image)| **marker == button::Marker::Help)
         .unwrap();
 
     match **help_enabled {
-        true => button_sprite.color = Color::hsl(120.0, 1.0, 0.5),
-        false => button_sprite.color = Color::default(),
+        true => button_image.color = palette.color(highlight::Highlight::Hint),
+        false => button_image.color = Color::default(),
     }
 }
 
-fn help(
-    mut tiles: Query<
-        (
-            Entity,
-            &tile::Variant,
-            &tile::Position,
-            &mut Sprite,
-            &mut Visibility,
-        ),
-        (With<tile::Marker<0>>, Without<marker::Hidden>),
-    >,
-    selected_tile: Res<SelectedTile>,
-    help_enabled: Res<HelpEnabled>,
-    mut prev_selection: Local<Option<Entity>>,
-) {
-    match selected_tile.0 {
-        Some(selected_tile) => {
-            if !**help_enabled {
-                *prev_selection = None;
-
-                let (
-                    selected_entity,
-                    selected_variant,
-                    _selected_position,
-                    _selected_sprite,
-                    _selected_visiblity,
-                ) = tiles.get(selected_tile).unwrap();
-
-                let mut entities_to_reset = vec![];
-
-                for (entity, variant, _position, _sprite, _visibility) in &tiles {
-                    if selected_entity != entity && *selected_variant == *variant {
-                        entities_to_reset.push(entity);
-                    }
-                }
-
-                for entity in entities_to_reset {
-                    let (_entity, _variant, _position, mut sprite, _visiblity) =
-                        tiles.get_mut(entity).unwrap();
-                    sprite.color = tile::DEFAULT_COLOR;
-                }
-                return;
-            }
-
-            if let Some(prev_selection) = *prev_selection {
-                if prev_selection == selected_tile {
-                    return;
-                }
-            }
-
-            *prev_selection = Some(selected_tile);
-
-            let (
-                selected_entity,
-                selected_variant,
-                _selected_position,
-                _selected_sprite,
-                _selected_visiblity,
-            ) = tiles.get(selected_tile).unwrap();
-
-            let mut entities_to_update = vec![];
-            let mut entities_to_reset = vec![];
-
-            for (entity, variant, _position, _sprite, _visibility) in &tiles {
-                if selected_entity != entity && *selected_variant == *variant {
-                    entities_to_update.push(entity);
-                } else if selected_entity != entity {
-                    entities_to_reset.push(entity);
-                }
-            }
-
-            for entity in entities_to_update {
-                let (_entity, _variant, _position, mut sprite, _visiblity) =
-                    tiles.get_mut(entity).unwrap();
-                sprite.color = Color::hsl(120.0, 1.0, 0.5);
-            }
-
-            for entity in entities_to_reset {
-                let (_entity, _variant, _position, mut sprite, _visiblity) =
-                    tiles.get_mut(entity).unwrap();
-                sprite.color = tile::DEFAULT_COLOR;
-            }
-        },
-        None => {
-            *prev_selection = None;
-            for (_entity, _variant, _position, mut sprite, _visibility) in &mut tiles {
-                sprite.color = tile::DEFAULT_COLOR;
-            }
-        },
-    }
+/// What running out of moves changes.
+#[derive(SystemParam)]
+struct Outcome<'w> {
+    progress: ResMut<'w, rules::Progress>,
+    players: ResMut<'w, players::Players>,
+    next_state: ResMut<'w, NextState<InGame>>,
 }
 
 fn update_move_count(
-    info_texts: Query<(&mut Text2d, &info::Marker)>,
-    positions: Query<
-        (&tile::Position, &tile::Variant),
-        (With<tile::Marker<0>>, Without<marker::Hidden>),
-    >,
-    mut board_updated: MessageReader<BoardUpdated>,
-    mut next_state: ResMut<NextState<InGame>>,
+    info_values: Query<(&mut TextSpan, &info::Marker)>,
+    tiles: Query<(Entity, &tile::Variant, &tile::Position, &players::Player), InPlay>,
+    free_tiles: Res<free::FreeTiles>,
+    rules: Res<rules::Rules>,
+    index: Res<board::BoardIndex>,
+    outcome: Outcome,
 ) {
-    if board_updated.read().count() == 0 {
-        return;
-    }
-
-    let len = positions.iter().len();
-
-    if len == 0 || len % 2 != 0 {
+    if !free_tiles.is_changed() {
         return;
     }
 
-    let mut free_variants: HashMap<u32, u32> = HashMap::new();
-
-    'outer: for (index0, (p0, v0)) in positions.iter().enumerate() {
-        let mut is_free_horizontally_counter: u32 = 0;
+    let Outcome {
+        mut progress,
+        mut players,
+        mut next_state,
+    } = outcome;
 
-        for (index1, (p1, v1)) in positions.iter().enumerate() {
-            if index0 == index1 {
-                continue;
-            }
+    // Only counted on boards still being played.
+    let mut moves: [Option<u32>; players::PLAYERS] = [None; players::PLAYERS];
 
-            let overlap = p0.x.abs_diff(p1.x) < 2 && p0.y.abs_diff(p1.y) < 2;
+    for player in players.boards() {
+        let tiles: Vec<_> = tiles
+            .iter()
+            .filter(|(.., other)| **other == player)
+            .collect();
+        let len = tiles.len();
 
-            if overlap {
-                let is_free_vertically = p0.z > p1.z;
-                if !is_free_vertically {
-                    continue 'outer;
-                }
-            }
+        if len == 0 || len % 2 != 0 {
+            continue;
+        }
 
-            let same_layer = p0.z == p1.z;
-            if !same_layer {
-                continue;
-            }
+        let free: Vec<(tile::Variant, tile::Position)> = tiles
+            .iter()
+            .filter(|(entity, ..)| free_tiles.contains(*entity))
+            .map(|(_, variant, position, _)| (**variant, **position))
+            .collect();
 
-            let is_same_row = p0.y.abs_diff(p1.y) < 2;
-            let is_next_to = is_same_row && p0.x.abs_diff(p1.x) == 2;
+        moves[player.0] = Some(rules.get().moves(&free, index.get(player)));
+    }
 
-            if is_next_to {
-                is_free_horizontally_counter += 1;
+    for (mut info_value, info_marker) in info_values {
+        let player = match info_marker {
+            info::Marker::Moves => 0,
+            info::Marker::PlayerMoves(player) => *player,
+            _ => continue,
+        };
 
-                if is_free_horizontally_counter >= 2 {
-                    continue 'outer;
-                }
-            }
+        if let Some(moves) = moves[player] {
+            info_value.0 = moves.to_string();
         }
+    }
 
-        if let Some(count) = free_variants.get_mut(&(v0.0)) {
-            *count += 1;
-        } else {
-            free_variants.insert(v0.0, 1);
+    // Running out of moves only defeats a single player. Otherwise it decides the winner.
+    if players.multiplayer() != players::Multiplayer::Off {
+        if let Some(player) = players.boards().find(|player| moves[player.0] == Some(0)) {
+            next_state.set(players.finish(player, false));
         }
+        return;
     }
 
-    let moves = free_variants
-        .iter()
-        .fold(0, |acc, (_variant, count)| acc + (*count / 2));
+    let Some(moves) = moves[0] else {
+        return;
+    };
 
-    for (mut info_text, info_marker) in info_texts {
-        if matches!(info_marker, info::Marker::Moves) {
-            info_text.0 = format!("Moves:\n{moves}").to_string();
-        }
-    }
+    progress.moves = Some(moves);
 
-    if moves < 1 {
+    if rules.get().defeated(&progress) {
         next_state.set(InGame::Defeat);
     }
 }
@@ -2445,6 +1722,13 @@ fn new_game_mouse(
     }
 }
 
+fn settings_mouse(
+    _on_press: On<Pointer<Press>>,
+    mut open: ResMut<settings::Open>,
+) {
+    open.0 = true;
+}
+
 fn poll_new_seed(
     mut msg: MessageReader<platform::SeedChanged>,
     mut next_state: ResMut<NextState<InGame>>,
@@ -2458,13 +1742,13 @@ fn poll_new_seed(
 fn spawn_finished(
     mut commands: Commands,
     projection: Query<&Projection, With<Camera>>,
-    asset_server: Res<AssetServer>,
+    manifest: Res<Manifest>,
 ) {
     let Some(Projection::Orthographic(projection)) = projection.iter().next() else {
         panic!();
     };
 
-    let handle: Handle<Image> = asset_server.load("misc/rev2/original/Victory.png");
+    let handle = manifest.victory.clone();
 
     spawn(
         &mut commands,
@@ -2482,13 +1766,13 @@ fn spawn_finished(
 fn spawn_defeat(
     mut commands: Commands,
     projection: Query<&Projection, With<Camera>>,
-    asset_server: Res<AssetServer>,
+    manifest: Res<Manifest>,
 ) {
     let Some(Projection::Orthographic(projection)) = projection.iter().next() else {
         panic!();
     };
 
-    let handle: Handle<Image> = asset_server.load("misc/rev2/original/Defeat.png");
+    let handle = manifest.defeat.clone();
 
     spawn(
         &mut commands,
@@ -0,0 +1,567 @@
+use crate::plugin::{
+    default::OverlayCamera,
+    locale::{self, Tr},
+    platform::{Platform, PlatformTrait},
+    scene::main_menu::{
+        MainMenu,
+        settings::{AppExt, Setting},
+    },
+};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, time::Duration};
+
+use connection::Connection;
+
+use super::{InGame, TilePositionVariantPairs, hud, marker, players, rules, tile};
+
+pub struct Plugin;
+
+impl bevy::prelude::Plugin for Plugin {
+    fn build(
+        &self,
+        app: &mut App,
+    ) {
+        app.init_resource::<Online>()
+            .init_resource::<Opponents>()
+            .init_non_send_resource::<Link>()
+            .add_systems(OnEnter(InGame::Running), join)
+            .add_systems(OnEnter(InGame::Victory), finish)
+            .add_systems(
+                Update,
+                (exchange, show_opponents)
+                    .chain()
+                    .run_if(in_state(MainMenu::Play)),
+            )
+            .add_setting(Setting {
+                label: "settings.online",
+                value: |world| {
+                    if world.resource::<Online>().enabled {
+                        locale::translate(world, "settings.on")
+                    } else {
+                        locale::translate(world, "settings.off")
+                    }
+                },
+                adjust: |world, _| {
+                    let mut online = world.resource_mut::<Online>();
+                    online.enabled = !online.enabled;
+                },
+            });
+    }
+}
+
+/// Relay used unless [PlatformTrait::relay] names another one, see the `mahjong-relay` crate.
+const DEFAULT_RELAY: &str = "ws://127.0.0.1:9001";
+
+/// How often progress is sent even though it did not change, so that late joiners see it.
+const HEARTBEAT: Duration = Duration::from_secs(1);
+
+/// Opponents which have not been heard from for this long are considered gone.
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+const BAR_SIZE: Vec2 = Vec2::new(160.0, 14.0);
+const BAR_COLOR: Color = Color::srgba(0.0, 0.0, 0.0, 0.6);
+const FILL_COLOR: Color = Color::srgb_u8(239, 191, 4);
+
+/// Whether games are raced online, and how far this player has come in the current one. A new
+/// selection takes effect from the next game.
+#[derive(Resource, Debug)]
+pub struct Online {
+    enabled: bool,
+    /// Tells this player apart from others racing in the same room.
+    id: u16,
+    remaining: u32,
+    finished: Option<Duration>,
+    heartbeat: Timer,
+    /// Progress changed since it was last sent.
+    pending: bool,
+}
+
+impl Default for Online {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            id: rand::random(),
+            remaining: 0,
+            finished: None,
+            heartbeat: Timer::new(HEARTBEAT, TimerMode::Repeating),
+            pending: false,
+        }
+    }
+}
+
+/// What racers tell each other through the relay.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct Report {
+    id: u16,
+    /// Pairs left on the board.
+    remaining: u32,
+    /// Pairs the board was dealt with.
+    total: u32,
+    /// Time taken to clear the board.
+    finished: Option<Duration>,
+}
+
+struct Opponent {
+    report: Report,
+    /// [Time::elapsed] when last heard from.
+    seen: Duration,
+}
+
+/// Others racing the same deal, by [Report::id].
+#[derive(Resource, Default, Deref, DerefMut)]
+struct Opponents(HashMap<u16, Opponent>);
+
+/// The open connection, if racing online, and the room it joined. Rooms are named after the rule
+/// set and the seed, so only players dealt the same board meet. Without a connection, a room means
+/// the relay could not be reached or went away.
+// Resource: NonSend, a WebSocket can not leave the main thread on the web.
+#[derive(Default)]
+struct Link {
+    connection: Option<Connection>,
+    room: String,
+}
+
+fn join(
+    mut online: ResMut<Online>,
+    mut link: NonSendMut<Link>,
+    mut opponents: ResMut<Opponents>,
+    platform: Res<Platform>,
+    rules: Res<rules::Rules>,
+) {
+    opponents.clear();
+    online.finished = None;
+    online.pending = true;
+
+    let seed = platform.rng_seed_get().filter(|_| online.enabled);
+    let Some(seed) = seed else {
+        link.connection = None;
+        link.room.clear();
+        return;
+    };
+
+    let room = format!("{}/{seed}", rules.get().key());
+    let reconnect = link
+        .connection
+        .as_ref()
+        .is_none_or(|connection| connection.is_closed());
+
+    if reconnect || link.room != room {
+        let url = platform
+            .relay()
+            .unwrap_or_else(|| DEFAULT_RELAY.to_string());
+        info!("Racing in {room} on {url}");
+        link.connection = Connection::open(&url, &room);
+        link.room = room;
+    }
+}
+
+fn finish(
+    mut online: ResMut<Online>,
+    progress: Res<rules::Progress>,
+) {
+    online.remaining = 0;
+    online.finished = Some(progress.elapsed);
+    online.pending = true;
+}
+
+/// Reads the progress of opponents and sends our own, when changed or on every [HEARTBEAT].
+fn exchange(
+    mut online: ResMut<Online>,
+    mut link: NonSendMut<Link>,
+    mut opponents: ResMut<Opponents>,
+    tiles: Query<&players::Player, (With<tile::Marker<0>>, Without<marker::Hidden>)>,
+    tile_position_variant_pairs: Res<TilePositionVariantPairs>,
+    state: Res<State<InGame>>,
+    time: Res<Time>,
+) {
+    let Some(connection) = &link.connection else {
+        return;
+    };
+
+    let now = time.elapsed();
+
+    for text in connection.receive() {
+        match ron::from_str::<Report>(&text) {
+            Ok(report) => {
+                opponents.insert(report.id, Opponent { report, seen: now });
+            },
+            Err(error) => warn!("Invalid progress from relay: {error}"),
+        }
+    }
+
+    if connection.is_closed() {
+        link.connection = None;
+        return;
+    }
+
+    if opponents
+        .values()
+        .any(|opponent| now - opponent.seen > TIMEOUT)
+    {
+        opponents.retain(|_, opponent| now - opponent.seen <= TIMEOUT);
+    }
+
+    // Only the board of the first player races, also in local multiplayer games.
+    if *state.get() == InGame::Running {
+        let remaining = tiles
+            .iter()
+            .filter(|player| **player == players::Player(0))
+            .count() as u32
+            / 2;
+
+        if online.remaining != remaining {
+            online.remaining = remaining;
+            online.pending = true;
+        }
+    }
+
+    let heartbeat = online.heartbeat.tick(time.delta()).just_finished();
+
+    if !(heartbeat || online.pending) || *state.get() == InGame::Init {
+        return;
+    }
+
+    let report = Report {
+        id: online.id,
+        remaining: online.remaining,
+        total: tile_position_variant_pairs.len() as u32 / 2,
+        finished: online.finished,
+    };
+
+    match ron::to_string(&report) {
+        Ok(text) => connection.send(text),
+        Err(error) => warn!("Could not encode progress: {error}"),
+    }
+    online.pending = false;
+}
+
+/// Panel with the game code and a progress bar per opponent.
+#[derive(Component)]
+struct Panel;
+
+fn show_opponents(
+    mut commands: Commands,
+    opponents: Res<Opponents>,
+    link: NonSend<Link>,
+    panels: Query<Entity, With<Panel>>,
+    camera: Single<Entity, With<OverlayCamera>>,
+    mut tr: Tr,
+) {
+    if !tr.changed() && !opponents.is_changed() && !link.is_changed() {
+        return;
+    }
+
+    for panel in panels {
+        commands.entity(panel).despawn();
+    }
+
+    if link.room.is_empty() {
+        return;
+    }
+
+    let font = hud::font(hud::element_size().y / 8.0);
+    let code = link.room.rsplit('/').next().unwrap_or_default();
+
+    let panel = commands
+        .spawn((
+            Panel,
+            DespawnOnExit(MainMenu::Play),
+            UiTargetCamera(*camera),
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(0.0),
+                top: Val::Percent(40.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(6.0),
+                padding: UiRect::all(Val::Px(8.0)),
+                ..default()
+            },
+            BackgroundColor(BAR_COLOR),
+            Pickable::IGNORE,
+            children![(
+                Text::new(format!("{} {code}", tr.get("online.code"))),
+                font.clone(),
+                Pickable::IGNORE,
+            )],
+        ))
+        .id();
+
+    if link.connection.is_none() {
+        commands.spawn((
+            ChildOf(panel),
+            Text::new(tr.get("online.unreachable")),
+            font,
+            Pickable::IGNORE,
+        ));
+        return;
+    }
+
+    // Finishers first, fastest first, then whoever is closest to finishing.
+    let mut reports: Vec<_> = opponents
+        .values()
+        .map(|opponent| &opponent.report)
+        .collect();
+    reports.sort_by_key(|report| (report.finished.is_none(), report.finished, report.remaining));
+
+    for report in reports {
+        let cleared = report.total.saturating_sub(report.remaining);
+        let status = match report.finished {
+            Some(time) => rules::clock(time),
+            None => format!("{cleared}/{}", report.total),
+        };
+        let fill = if report.total == 0 {
+            0.0
+        } else {
+            100.0 * cleared as f32 / report.total as f32
+        };
+
+        commands.spawn((
+            ChildOf(panel),
+            Node {
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            Pickable::IGNORE,
+            children![
+                (
+                    Text::new(format!("#{:04x} {status}", report.id)),
+                    font.clone(),
+                    Pickable::IGNORE,
+                ),
+                (
+                    Node {
+                        width: Val::Px(BAR_SIZE.x),
+                        height: Val::Px(BAR_SIZE.y),
+                        ..default()
+                    },
+                    BackgroundColor(BAR_COLOR),
+                    Pickable::IGNORE,
+                    children![(
+                        Node {
+                            width: Val::Percent(fill),
+                            height: Val::Percent(100.0),
+                            ..default()
+                        },
+                        BackgroundColor(FILL_COLOR),
+                        Pickable::IGNORE,
+                    )],
+                ),
+            ],
+        ));
+    }
+}
+
+/// NATIVE
+#[cfg(not(target_arch = "wasm32"))]
+mod connection {
+    use bevy::log::warn;
+    use std::{
+        cell::Cell,
+        io::ErrorKind,
+        net::TcpStream,
+        sync::mpsc::{self, Receiver, Sender, TryRecvError},
+        thread,
+        time::Duration,
+    };
+    use tungstenite::{Error, Message, WebSocket, stream::MaybeTlsStream};
+
+    /// How long the socket waits for a message before sending those of the game.
+    const POLL: Duration = Duration::from_millis(50);
+
+    /// Socket to the relay, served by a thread of its own.
+    pub struct Connection {
+        outgoing: Sender<String>,
+        incoming: Receiver<String>,
+        closed: Cell<bool>,
+    }
+
+    impl Connection {
+        /// Connects to the relay at `url` and joins `room` on a thread of its own, so that the
+        /// game goes on while it does. A relay which can not be reached closes the connection, see
+        /// [Connection::is_closed].
+        pub fn open(
+            url: &str,
+            room: &str,
+        ) -> Option<Self> {
+            let (outgoing, outgoing_receiver) = mpsc::channel();
+            let (incoming_sender, incoming) = mpsc::channel();
+            let url = url.to_string();
+            let room = room.to_string();
+
+            // Dropping the sender when done disconnects the incoming channel.
+            thread::spawn(move || {
+                let served = connect(&url, &room)
+                    .and_then(|socket| serve(socket, &outgoing_receiver, &incoming_sender));
+
+                if let Err(error) = served {
+                    warn!("Relay {url}: {error}");
+                }
+            });
+
+            Some(Self {
+                outgoing,
+                incoming,
+                closed: Cell::new(false),
+            })
+        }
+
+        pub fn send(
+            &self,
+            text: String,
+        ) {
+            let _ = self.outgoing.send(text);
+        }
+
+        pub fn receive(&self) -> Vec<String> {
+            let mut texts = Vec::new();
+
+            loop {
+                match self.incoming.try_recv() {
+                    Ok(text) => texts.push(text),
+                    Err(TryRecvError::Empty) => break,
+                    Err(TryRecvError::Disconnected) => {
+                        self.closed.set(true);
+                        break;
+                    },
+                }
+            }
+
+            texts
+        }
+
+        pub fn is_closed(&self) -> bool {
+            self.closed.get()
+        }
+    }
+
+    type Socket = WebSocket<MaybeTlsStream<TcpStream>>;
+
+    fn connect(
+        url: &str,
+        room: &str,
+    ) -> Result<Socket, Error> {
+        let (mut socket, _) = tungstenite::connect(url)?;
+
+        if let MaybeTlsStream::Plain(stream) = socket.get_ref() {
+            stream.set_read_timeout(Some(POLL))?;
+        }
+
+        socket.send(Message::text(room))?;
+
+        Ok(socket)
+    }
+
+    fn serve(
+        mut socket: Socket,
+        outgoing: &Receiver<String>,
+        incoming: &Sender<String>,
+    ) -> Result<(), Error> {
+        loop {
+            match socket.read() {
+                Ok(Message::Text(text)) => {
+                    if incoming.send(text.to_string()).is_err() {
+                        return Ok(());
+                    }
+                },
+                Ok(Message::Close(_)) => return Ok(()),
+                Ok(_) => {},
+                Err(Error::Io(error))
+                    if matches!(error.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {},
+                Err(error) => return Err(error),
+            }
+
+            loop {
+                match outgoing.try_recv() {
+                    Ok(text) => socket.send(Message::text(text))?,
+                    Err(TryRecvError::Empty) => break,
+                    // The game is done racing.
+                    Err(TryRecvError::Disconnected) => {
+                        socket.close(None)?;
+                        return Ok(());
+                    },
+                }
+            }
+        }
+    }
+}
+
+/// WASM
+#[cfg(target_arch = "wasm32")]
+mod connection {
+    use bevy::log::warn;
+    use std::{cell::RefCell, collections::VecDeque, rc::Rc};
+    use wasm_bindgen::{JsCast, prelude::*};
+    use web_sys::{MessageEvent, WebSocket};
+
+    /// Socket to the relay, fed by browser callbacks.
+    pub struct Connection {
+        socket: WebSocket,
+        incoming: Rc<RefCell<VecDeque<String>>>,
+        _on_open: Closure<dyn FnMut()>,
+        _on_message: Closure<dyn FnMut(MessageEvent)>,
+    }
+
+    impl Connection {
+        pub fn open(
+            url: &str,
+            room: &str,
+        ) -> Option<Self> {
+            let socket = WebSocket::new(url)
+                .inspect_err(|error| warn!("Relay {url}: {error:?}"))
+                .ok()?;
+            let incoming = Rc::new(RefCell::new(VecDeque::new()));
+
+            let on_open = Closure::wrap(Box::new({
+                let socket = socket.clone();
+                let room = room.to_string();
+                move || {
+                    let _ = socket.send_with_str(&room);
+                }
+            }) as Box<dyn FnMut()>);
+            socket.set_onopen(Some(on_open.as_ref().unchecked_ref()));
+
+            let on_message = Closure::wrap(Box::new({
+                let incoming = Rc::clone(&incoming);
+                move |event: MessageEvent| {
+                    if let Some(text) = event.data().as_string() {
+                        incoming.borrow_mut().push_back(text);
+                    }
+                }
+            }) as Box<dyn FnMut(_)>);
+            socket.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+
+            Some(Self {
+                socket,
+                incoming,
+                _on_open: on_open,
+                _on_message: on_message,
+            })
+        }
+
+        pub fn send(
+            &self,
+            text: String,
+        ) {
+            if self.socket.ready_state() == WebSocket::OPEN {
+                let _ = self.socket.send_with_str(&text);
+            }
+        }
+
+        pub fn receive(&self) -> Vec<String> {
+            self.incoming.borrow_mut().drain(..).collect()
+        }
+
+        pub fn is_closed(&self) -> bool {
+            self.socket.ready_state() == WebSocket::CLOSED
+        }
+    }
+
+    impl Drop for Connection {
+        fn drop(&mut self) {
+            self.socket.set_onopen(None);
+            self.socket.set_onmessage(None);
+            let _ = self.socket.close();
+        }
+    }
+}
@@ -0,0 +1,381 @@
+use crate::plugin::{
+    default::{OVERLAY_LAYER, OverlayCamera},
+    locale::{self, Tr},
+    scene::main_menu::settings::{AppExt, Setting},
+};
+use bevy::{
+    app::{HierarchyPropagatePlugin, PropagateSet},
+    camera::{
+        ScalingMode, Viewport,
+        visibility::{RenderLayers, VisibilitySystems},
+    },
+    prelude::*,
+    window::PrimaryWindow,
+};
+
+use super::{InGame, hud, info, marker};
+
+pub struct Plugin;
+
+impl bevy::prelude::Plugin for Plugin {
+    fn build(
+        &self,
+        app: &mut App,
+    ) {
+        app.add_plugins(HierarchyPropagatePlugin::<RenderLayers>::new(PostUpdate))
+            .configure_sets(
+                PostUpdate,
+                PropagateSet::<RenderLayers>::default().before(VisibilitySystems::CheckVisibility),
+            )
+            .init_resource::<Players>()
+            .add_systems(OnEnter(InGame::Root), start)
+            .add_systems(OnEnter(InGame::Init), spawn_camera)
+            .add_systems(OnEnter(InGame::Victory), spawn_result)
+            .add_systems(Update, layout)
+            .add_systems(Update, show_scores.run_if(in_state(InGame::Running)))
+            .add_setting(Setting {
+                label: "settings.players",
+                value: |world| locale::translate(world, world.resource::<Players>().selected.key()),
+                adjust: |world, delta| {
+                    let mut players = world.resource_mut::<Players>();
+                    players.selected = players.selected.step(delta);
+                },
+            });
+    }
+}
+
+/// Most players a game can have.
+pub const PLAYERS: usize = 2;
+
+/// Render layer of the second board in versus games, see [VersusCamera]. The first board stays on
+/// the default layer.
+const VERSUS_LAYER: usize = OVERLAY_LAYER + 1;
+
+/// Width to height ratio which a whole board fits in.
+const BOARD_ASPECT: f32 = 16.0 / 9.0;
+
+/// Slab color of the player whose turn it is.
+const TURN_COLOR: Color = Color::hsl(45.0, 0.8, 0.7);
+
+/// Player which a board, its tiles and its info belong to.
+#[derive(Component, Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+pub struct Player(pub usize);
+
+impl Player {
+    /// Layers the board of this player is rendered on.
+    pub fn render_layers(&self) -> RenderLayers {
+        match self.0 {
+            0 => RenderLayers::layer(0),
+            _ => RenderLayers::layer(VERSUS_LAYER),
+        }
+    }
+}
+
+/// How many play, and how.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum Multiplayer {
+    #[default]
+    Off,
+    /// Players take turns removing pairs from the same board.
+    HotSeat,
+    /// Players race on boards of their own, dealt from the same seed.
+    Versus,
+}
+
+impl Multiplayer {
+    pub const ALL: [Multiplayer; 3] = [Multiplayer::Off, Multiplayer::HotSeat, Multiplayer::Versus];
+
+    /// Message key of the name.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Multiplayer::Off => "players.single",
+            Multiplayer::HotSeat => "players.hot_seat",
+            Multiplayer::Versus => "players.versus",
+        }
+    }
+
+    fn step(
+        &self,
+        delta: i32,
+    ) -> Self {
+        let index = Self::ALL.iter().position(|mode| mode == self).unwrap() as i32;
+        Self::ALL[(index + delta).rem_euclid(Self::ALL.len() as i32) as usize]
+    }
+}
+
+/// The [Multiplayer] chosen in the settings, and the score of the current game. A new selection
+/// takes effect from the next game.
+#[derive(Resource, Default, Debug)]
+pub struct Players {
+    selected: Multiplayer,
+    multiplayer: Multiplayer,
+    /// Player to remove the next pair in hot-seat games.
+    pub turn: usize,
+    /// Pairs removed by each player.
+    pub scores: [u32; PLAYERS],
+    /// Decided when the game ends. [None] on a draw, or when playing alone.
+    pub winner: Option<usize>,
+}
+
+impl Players {
+    pub fn multiplayer(&self) -> Multiplayer {
+        self.multiplayer
+    }
+
+    /// Players with a board of their own.
+    pub fn boards(&self) -> impl Iterator<Item = Player> + use<> {
+        let boards = match self.multiplayer {
+            Multiplayer::Versus => PLAYERS,
+            _ => 1,
+        };
+
+        (0..boards).map(Player)
+    }
+
+    /// Taking back moves has no place in a competitive game.
+    pub fn allows_undo(&self) -> bool {
+        self.multiplayer == Multiplayer::Off
+    }
+
+    /// Credits a pair removed from the board of `player`. On a shared board, it goes to whoever's
+    /// turn it is.
+    pub fn scored(
+        &mut self,
+        player: Player,
+    ) {
+        match self.multiplayer {
+            Multiplayer::Off => {},
+            Multiplayer::HotSeat => self.scores[self.turn] += 1,
+            Multiplayer::Versus => self.scores[player.0] += 1,
+        }
+    }
+
+    /// Hands the turn to the next player in hot-seat games.
+    pub fn pass(&mut self) {
+        if self.multiplayer == Multiplayer::HotSeat {
+            self.turn = (self.turn + 1) % PLAYERS;
+        }
+    }
+
+    /// Ends the game once the board of `player` is cleared, or out of moves. Returns the state to
+    /// go to. Playing alone, running out of moves is a defeat. Otherwise it decides the winner.
+    pub fn finish(
+        &mut self,
+        player: Player,
+        cleared: bool,
+    ) -> InGame {
+        match self.multiplayer {
+            Multiplayer::Off if cleared => return InGame::Victory,
+            Multiplayer::Off => return InGame::Defeat,
+            Multiplayer::HotSeat => self.winner = self.leader(),
+            Multiplayer::Versus if cleared => self.winner = Some(player.0),
+            Multiplayer::Versus => self.winner = Some((player.0 + 1) % PLAYERS),
+        }
+
+        InGame::Victory
+    }
+
+    /// Ends the game once time runs out on every board. Returns the state to go to. Playing alone,
+    /// it is a defeat. Otherwise the player who removed more pairs wins, equal scores are a draw.
+    pub fn time_out(&mut self) -> InGame {
+        if self.multiplayer == Multiplayer::Off {
+            return InGame::Defeat;
+        }
+
+        self.winner = self.leader();
+        InGame::Victory
+    }
+
+    /// Player with the higher score, [None] when tied.
+    fn leader(&self) -> Option<usize> {
+        let [first, second] = self.scores;
+
+        match first.cmp(&second) {
+            std::cmp::Ordering::Greater => Some(0),
+            std::cmp::Ordering::Less => Some(1),
+            std::cmp::Ordering::Equal => None,
+        }
+    }
+}
+
+fn start(mut players: ResMut<Players>) {
+    *players = Players {
+        selected: players.selected,
+        multiplayer: players.selected,
+        ..default()
+    };
+    info!("Playing {:?}", players.multiplayer);
+}
+
+/// Renders the second board of a versus game, next to the first one.
+#[derive(Component)]
+struct VersusCamera;
+
+fn spawn_camera(
+    mut commands: Commands,
+    players: Res<Players>,
+) {
+    if players.multiplayer != Multiplayer::Versus {
+        return;
+    }
+
+    commands.spawn((
+        VersusCamera,
+        DespawnOnExit(InGame::Running),
+        Camera2d,
+        Projection::Orthographic(OrthographicProjection {
+            scaling_mode: ScalingMode::FixedVertical {
+                viewport_height: 1080.0,
+            },
+            ..OrthographicProjection::default_2d()
+        }),
+        Msaa::Off,
+        Camera {
+            clear_color: ClearColorConfig::None,
+            order: -1,
+            ..Camera::default()
+        },
+        RenderLayers::layer(VERSUS_LAYER),
+    ));
+}
+
+type BoardBackground = (Entity, &'static mut Transform, Has<RenderLayers>);
+
+/// Splits the window between the boards while a [VersusCamera] exists, and zooms out until each
+/// board fits its half. Restores the single full window view otherwise.
+fn layout(
+    mut commands: Commands,
+    window: Single<&Window, With<PrimaryWindow>>,
+    mut cameras: Query<(&mut Camera, &mut Transform, Has<VersusCamera>), Without<OverlayCamera>>,
+    mut backgrounds: Query<BoardBackground, (With<marker::Background>, Without<Camera>)>,
+) {
+    let versus = cameras.iter().any(|(.., versus)| versus);
+    let size = window.physical_size();
+    let half = UVec2::new(size.x / 2, size.y);
+
+    let scale = if versus && half.x != 0 {
+        (BOARD_ASPECT * half.y as f32 / half.x as f32).max(1.0)
+    } else {
+        1.0
+    };
+    let scale = Vec3::new(scale, scale, 1.0);
+
+    for (mut camera, mut transform, is_versus) in &mut cameras {
+        let area = versus.then(|| (UVec2::new(if is_versus { half.x } else { 0 }, 0), half));
+        let current = camera
+            .viewport
+            .as_ref()
+            .map(|viewport| (viewport.physical_position, viewport.physical_size));
+
+        if current != area {
+            camera.viewport = area.map(|(physical_position, physical_size)| Viewport {
+                physical_position,
+                physical_size,
+                ..default()
+            });
+        }
+
+        if transform.scale != scale {
+            transform.scale = scale;
+        }
+    }
+
+    for (entity, mut transform, has_render_layers) in &mut backgrounds {
+        if transform.scale != scale {
+            transform.scale = scale;
+        }
+
+        if versus && !has_render_layers {
+            commands
+                .entity(entity)
+                .insert(RenderLayers::from_layers(&[0, VERSUS_LAYER]));
+        }
+    }
+}
+
+fn show_scores(
+    players: Res<Players>,
+    info_values: Query<(&mut TextSpan, &info::Marker)>,
+    mut slabs: Query<(&info::Marker, &mut ImageNode)>,
+) {
+    if !players.is_changed() {
+        return;
+    }
+
+    for (mut info_value, info_marker) in info_values {
+        if let info::Marker::Score(player) = info_marker {
+            info_value.0 = players.scores[*player].to_string();
+        }
+    }
+
+    for (info_marker, mut slab) in &mut slabs {
+        if let info::Marker::Score(player) = info_marker {
+            slab.color = if players.multiplayer == Multiplayer::HotSeat && players.turn == *player {
+                TURN_COLOR
+            } else {
+                info::SLAB_COLOR
+            };
+        }
+    }
+}
+
+fn spawn_result(
+    mut commands: Commands,
+    players: Res<Players>,
+    camera: Single<Entity, With<OverlayCamera>>,
+    tr: Tr,
+) {
+    if players.multiplayer == Multiplayer::Off {
+        return;
+    }
+
+    let key = match players.winner {
+        Some(0) => "players.winner_1",
+        Some(_) => "players.winner_2",
+        None => "players.draw",
+    };
+    let [first, second] = players.scores;
+
+    commands.spawn((
+        DespawnOnExit(InGame::Victory),
+        UiTargetCamera(*camera),
+        Node {
+            width: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            padding: UiRect::top(Val::Percent(5.0)),
+            ..default()
+        },
+        Pickable::IGNORE,
+        children![(
+            Text::new(format!("{}\n{first} - {second}", tr.get(key))),
+            TextLayout::new_with_justify(Justify::Center),
+            hud::font(hud::element_size().y / 3.0),
+            Pickable::IGNORE,
+        )],
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn versus(scores: [u32; PLAYERS]) -> Players {
+        Players {
+            multiplayer: Multiplayer::Versus,
+            scores,
+            ..default()
+        }
+    }
+
+    #[test]
+    fn time_out_decides_by_score() {
+        for (scores, winner) in [([3, 5], Some(1)), ([5, 3], Some(0)), ([4, 4], None)] {
+            let mut players = versus(scores);
+
+            assert_eq!(players.time_out(), InGame::Victory);
+            assert_eq!(players.winner, winner, "Scores {scores:?}");
+        }
+
+        assert_eq!(Players::default().time_out(), InGame::Defeat);
+    }
+}
@@ -0,0 +1,444 @@
+use crate::plugin::{
+    locale,
+    scene::main_menu::settings::{AppExt, Setting},
+};
+use bevy::prelude::*;
+use std::{collections::HashMap, time::Duration};
+
+use super::{InGame, board::Board, free, info, memory, players, shisen, tile};
+
+pub struct Plugin;
+
+impl bevy::prelude::Plugin for Plugin {
+    fn build(
+        &self,
+        app: &mut App,
+    ) {
+        app.init_resource::<Rules>()
+            .init_resource::<Progress>()
+            .add_systems(OnEnter(InGame::Root), start)
+            .add_systems(Update, tick.run_if(in_state(InGame::Running)))
+            .add_setting(Setting {
+                label: "settings.mode",
+                value: |world| {
+                    locale::translate(world, world.resource::<Rules>().selected_mode.key())
+                },
+                adjust: |world, delta| {
+                    let mut rules = world.resource_mut::<Rules>();
+                    rules.selected_mode = rules.selected_mode.step(delta);
+                },
+            })
+            .add_setting(Setting {
+                label: "settings.rules",
+                value: |world| locale::translate(world, world.resource::<Rules>().selected().key()),
+                adjust: |world, delta| world.resource_mut::<Rules>().step(delta),
+            });
+    }
+}
+
+/// Rules of a single game. Removal, move counting and defeat all dispatch through the active one,
+/// see [Rules::get].
+pub trait RuleSet: Send + Sync {
+    /// Message key of the name.
+    fn key(&self) -> &'static str;
+
+    /// Whether the tile at `position` may be removed. `board` are the positions of all tiles still
+    /// in play.
+    fn is_free(
+        &self,
+        position: &tile::Position,
+        board: &Board,
+    ) -> bool {
+        free::is_free(position, board)
+    }
+
+    /// Whether `a` and `b` may be removed as a pair.
+    fn valid_removal(
+        &self,
+        a: (&tile::Variant, &tile::Position),
+        b: (&tile::Variant, &tile::Position),
+        board: &Board,
+    ) -> bool {
+        a.0.matches(b.0) && self.is_free(a.1, board) && self.is_free(b.1, board)
+    }
+
+    /// Whether `candidate` is hinted at while `selected` is selected and help is enabled.
+    fn hint(
+        &self,
+        selected: (&tile::Variant, &tile::Position),
+        candidate: (&tile::Variant, &tile::Position),
+        board: &Board,
+    ) -> bool {
+        selected.0.matches(candidate.0) && self.is_free(candidate.1, board)
+    }
+
+    /// Number of pairs which can be removed, given all free tiles.
+    fn moves(
+        &self,
+        free: &[(tile::Variant, tile::Position)],
+        _board: &Board,
+    ) -> u32 {
+        let mut groups: HashMap<tile::MatchGroup, u32> = HashMap::new();
+
+        for (variant, _) in free {
+            *groups.entry(variant.match_group()).or_default() += 1;
+        }
+
+        groups.values().map(|count| count / 2).sum()
+    }
+
+    /// Time allowed to clear the board.
+    fn time_limit(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Whether the time spent is shown, in addition to what is left of any [RuleSet::time_limit].
+    fn timed(&self) -> bool {
+        self.time_limit().is_some()
+    }
+
+    /// Number of undos allowed per game.
+    fn undo_limit(&self) -> Option<u32> {
+        None
+    }
+
+    fn defeated(
+        &self,
+        progress: &Progress,
+    ) -> bool {
+        progress.moves == Some(0)
+            || self
+                .time_limit()
+                .is_some_and(|limit| progress.elapsed >= limit)
+    }
+}
+
+/// The traditional rules, see [free::is_free].
+pub struct Classic;
+
+impl RuleSet for Classic {
+    fn key(&self) -> &'static str {
+        "rules.classic"
+    }
+}
+
+/// Only a neighbour in line with the whole side blocks it. Half-offset neighbours never do.
+pub struct Open;
+
+impl RuleSet for Open {
+    fn key(&self) -> &'static str {
+        "rules.open"
+    }
+
+    fn is_free(
+        &self,
+        position: &tile::Position,
+        board: &Board,
+    ) -> bool {
+        if board.above(position).next().is_some() {
+            return false;
+        }
+
+        let blocked = |side| {
+            board
+                .neighbours(position, side)
+                .any(|other| other.y == position.y)
+        };
+
+        !(blocked(free::Side::Left) && blocked(free::Side::Right))
+    }
+}
+
+/// A tile which is only partly covered, e.g. by the half-offset top of the turtle, can be peeked
+/// under and removed.
+pub struct Peek;
+
+impl RuleSet for Peek {
+    fn key(&self) -> &'static str {
+        "rules.peek"
+    }
+
+    fn is_free(
+        &self,
+        position: &tile::Position,
+        board: &Board,
+    ) -> bool {
+        let fully_covered = board
+            .above(position)
+            .any(|other| other.x == position.x && other.y == position.y);
+
+        !fully_covered && !free::is_enclosed(position, board)
+    }
+}
+
+/// Classic rules, but the board has to be cleared before the clock runs out.
+pub struct TimeAttack;
+
+impl RuleSet for TimeAttack {
+    fn key(&self) -> &'static str {
+        "rules.time_attack"
+    }
+
+    fn time_limit(&self) -> Option<Duration> {
+        Some(Duration::from_secs(10 * 60))
+    }
+}
+
+/// Classic rules, with only a few undos per game.
+pub struct LimitedUndo;
+
+impl RuleSet for LimitedUndo {
+    fn key(&self) -> &'static str {
+        "rules.limited_undo"
+    }
+
+    fn undo_limit(&self) -> Option<u32> {
+        Some(3)
+    }
+}
+
+pub const RULE_SETS: [&'static dyn RuleSet; 5] =
+    [&Classic, &Open, &Peek, &TimeAttack, &LimitedUndo];
+
+/// Game played on the board.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum Mode {
+    /// Tiles are stacked, and removed by the selected [RuleSet].
+    #[default]
+    Solitaire,
+    /// Tiles are laid out flat, and removed when connected, see [shisen].
+    ShisenSho,
+    /// Tiles are laid out face down, and removed when turned up in matching pairs, see [memory].
+    Memory,
+}
+
+impl Mode {
+    pub const ALL: [Mode; 3] = [Mode::Solitaire, Mode::ShisenSho, Mode::Memory];
+
+    /// Message key of the name.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Mode::Solitaire => "mode.solitaire",
+            Mode::ShisenSho => "mode.shisen_sho",
+            Mode::Memory => "mode.memory",
+        }
+    }
+
+    fn step(
+        &self,
+        delta: i32,
+    ) -> Self {
+        let index = Self::ALL.iter().position(|mode| mode == self).unwrap() as i32;
+        Self::ALL[(index + delta).rem_euclid(Self::ALL.len() as i32) as usize]
+    }
+}
+
+/// The [Mode] and [RuleSet] chosen in the settings, and the ones the current game is played by. A
+/// new selection takes effect from the next game.
+#[derive(Resource)]
+pub struct Rules {
+    selected_mode: Mode,
+    mode: Mode,
+    selected: usize,
+    active: &'static dyn RuleSet,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self {
+            selected_mode: Mode::default(),
+            mode: Mode::default(),
+            selected: 0,
+            active: RULE_SETS[0],
+        }
+    }
+}
+
+impl Rules {
+    pub fn get(&self) -> &'static dyn RuleSet {
+        self.active
+    }
+
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    pub fn selected(&self) -> &'static dyn RuleSet {
+        RULE_SETS[self.selected]
+    }
+
+    /// Selects `mode` for the next game, as the settings do.
+    #[cfg(feature = "console")]
+    pub fn select_mode(
+        &mut self,
+        mode: Mode,
+    ) {
+        self.selected_mode = mode;
+    }
+
+    pub fn step(
+        &mut self,
+        delta: i32,
+    ) {
+        self.selected = (self.selected as i32 + delta).rem_euclid(RULE_SETS.len() as i32) as usize;
+    }
+}
+
+/// State of the current game which a [RuleSet] may end it on.
+#[derive(Resource, Default, Debug)]
+pub struct Progress {
+    /// Pairs which can currently be removed. [None] until first counted.
+    pub moves: Option<u32>,
+    pub elapsed: Duration,
+    pub undos: u32,
+}
+
+impl Progress {
+    /// Whether another undo is allowed by `rules`.
+    pub fn can_undo(
+        &self,
+        rules: &dyn RuleSet,
+    ) -> bool {
+        rules.undo_limit().is_none_or(|limit| self.undos < limit)
+    }
+}
+
+fn start(
+    mut rules: ResMut<Rules>,
+    mut progress: ResMut<Progress>,
+) {
+    rules.mode = rules.selected_mode;
+    rules.active = match rules.mode {
+        Mode::Solitaire => rules.selected(),
+        Mode::ShisenSho => &shisen::ShisenSho,
+        Mode::Memory => &memory::Memory,
+    };
+    *progress = Progress::default();
+    info!("Playing {:?} by {}", rules.mode, rules.get().key());
+}
+
+/// Formats `duration` as minutes and seconds.
+pub fn clock(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+fn tick(
+    time: Res<Time>,
+    rules: Res<Rules>,
+    mut progress: ResMut<Progress>,
+    info_values: Query<(&mut TextSpan, &info::Marker)>,
+    mut players: ResMut<players::Players>,
+    mut next_state: ResMut<NextState<InGame>>,
+) {
+    progress.elapsed += time.delta();
+
+    let rules = rules.get();
+    let time = rules
+        .time_limit()
+        .map(|limit| clock(limit.saturating_sub(progress.elapsed)));
+    let elapsed = rules.timed().then(|| clock(progress.elapsed));
+    let undos = rules
+        .undo_limit()
+        .map(|limit| limit.saturating_sub(progress.undos).to_string());
+
+    for (mut info_value, info_marker) in info_values {
+        let text = match info_marker {
+            info::Marker::Time => &time,
+            info::Marker::Elapsed => &elapsed,
+            info::Marker::Undos => &undos,
+            _ => continue,
+        };
+
+        if let Some(text) = text
+            && info_value.0 != *text
+        {
+            info_value.0 = text.clone();
+        }
+    }
+
+    if !rules.defeated(&progress) {
+        return;
+    }
+
+    next_state.set(players.time_out());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(
+        x: u32,
+        y: u32,
+        z: u32,
+    ) -> tile::Position {
+        tile::Position(UVec3::new(x, y, z))
+    }
+
+    #[test]
+    fn open_ignores_half_offset_neighbours() {
+        // Left neighbour half a tile lower, right one in line.
+        let board: Board = [at(0, 1, 0), at(2, 0, 0), at(4, 0, 0)]
+            .into_iter()
+            .collect();
+
+        assert!(!Classic.is_free(&at(2, 0, 0), &board));
+        assert!(Open.is_free(&at(2, 0, 0), &board));
+
+        let board: Board = [at(0, 0, 0), at(2, 0, 0), at(4, 0, 0)]
+            .into_iter()
+            .collect();
+
+        assert!(!Open.is_free(&at(2, 0, 0), &board));
+    }
+
+    #[test]
+    fn peek_reveals_partly_covered_tiles() {
+        // Covered by a tile half a tile to the right.
+        let board: Board = [at(2, 2, 0), at(3, 2, 1)].into_iter().collect();
+
+        assert!(!Classic.is_free(&at(2, 2, 0), &board));
+        assert!(Peek.is_free(&at(2, 2, 0), &board));
+
+        let board: Board = [at(2, 2, 0), at(2, 2, 1)].into_iter().collect();
+
+        assert!(!Peek.is_free(&at(2, 2, 0), &board));
+
+        let board: Board = [at(0, 2, 0), at(2, 2, 0), at(4, 2, 0)]
+            .into_iter()
+            .collect();
+
+        assert!(!Peek.is_free(&at(2, 2, 0), &board));
+    }
+
+    #[test]
+    fn limited_undo_runs_out() {
+        let mut progress = Progress::default();
+
+        for _ in 0..LimitedUndo.undo_limit().unwrap() {
+            assert!(progress.can_undo(&LimitedUndo));
+            progress.undos += 1;
+        }
+
+        assert!(!progress.can_undo(&LimitedUndo));
+        assert!(progress.can_undo(&Classic));
+    }
+
+    #[test]
+    fn time_attack_defeats_on_time_out() {
+        let limit = TimeAttack.time_limit().unwrap();
+        let mut progress = Progress {
+            moves: Some(4),
+            elapsed: limit - Duration::from_secs(1),
+            undos: 0,
+        };
+
+        assert!(!TimeAttack.defeated(&progress));
+
+        progress.elapsed = limit;
+
+        assert!(TimeAttack.defeated(&progress));
+        assert!(!Classic.defeated(&progress));
+    }
+}
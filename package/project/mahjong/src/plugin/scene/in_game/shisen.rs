@@ -0,0 +1,423 @@
+use crate::plugin::frame_budget::FrameBudget;
+use bevy::{platform::collections::HashSet, prelude::*};
+use rand::{SeedableRng, rngs::StdRng, seq::SliceRandom};
+use std::time::Duration;
+
+use super::{
+    InGame, PairRemoved,
+    board::{Board, BoardIndex},
+    highlight,
+    rules::{Mode, RuleSet, Rules},
+    theme, tile,
+};
+
+pub struct Plugin;
+
+impl bevy::prelude::Plugin for Plugin {
+    fn build(
+        &self,
+        app: &mut App,
+    ) {
+        app.add_systems(
+            Update,
+            (draw_path, fade_path).run_if(in_state(InGame::Running)),
+        );
+    }
+}
+
+/// Size of the board, in tiles.
+pub const COLUMNS: i32 = 12;
+pub const ROWS: i32 = 7;
+pub const TILES: usize = (COLUMNS * ROWS) as usize;
+
+/// Random playthroughs tried before a deal is considered unsolvable.
+const ATTEMPTS: usize = 20;
+
+/// Cell of the board a [tile::Position] is in. The board is centered on the columns of the turtle,
+/// hence the offset.
+fn cell(position: &tile::Position) -> IVec2 {
+    IVec2::new((position.x as i32 - 3) / 2, (position.y as i32 - 1) / 2)
+}
+
+fn position(cell: IVec2) -> tile::Position {
+    tile::Position(UVec3::new(3 + 2 * cell.x as u32, 1 + 2 * cell.y as u32, 0))
+}
+
+fn on_board(cell: IVec2) -> bool {
+    cell.x >= 0 && cell.y >= 0 && cell.x < COLUMNS && cell.y < ROWS
+}
+
+/// Paths may leave the board by one cell, i.e. go around its edge.
+fn in_bounds(cell: IVec2) -> bool {
+    cell.x >= -1 && cell.y >= -1 && cell.x <= COLUMNS && cell.y <= ROWS
+}
+
+const DIRECTIONS: [IVec2; 4] = [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y];
+
+/// `from` along with every empty cell reachable from it in a straight line.
+fn reach(
+    from: IVec2,
+    occupied: &HashSet<IVec2>,
+) -> Vec<IVec2> {
+    let mut cells = vec![from];
+
+    for direction in DIRECTIONS {
+        let mut cell = from + direction;
+
+        while in_bounds(cell) && !occupied.contains(&cell) {
+            cells.push(cell);
+            cell += direction;
+        }
+    }
+
+    cells
+}
+
+/// Whether `a` and `b` are in line, with nothing in between.
+fn clear(
+    a: IVec2,
+    b: IVec2,
+    occupied: &HashSet<IVec2>,
+) -> bool {
+    if a.x != b.x && a.y != b.y {
+        return false;
+    }
+
+    let step = (b - a).signum();
+    let mut cell = a + step;
+
+    while cell != b {
+        if occupied.contains(&cell) {
+            return false;
+        }
+        cell += step;
+    }
+
+    true
+}
+
+/// Every path connecting `a` and `b` through empty cells with at most two corners, each as a list
+/// of its corners from `a` to `b`. `board` are the positions of all tiles still in play.
+fn paths(
+    a: &tile::Position,
+    b: &tile::Position,
+    board: &Board,
+) -> impl Iterator<Item = Vec<IVec2>> {
+    let occupied: HashSet<IVec2> = board.iter().map(cell).collect();
+    let (start, end) = (cell(a), cell(b));
+    let from_start = if start == end {
+        Vec::new()
+    } else {
+        reach(start, &occupied)
+    };
+    let from_end = reach(end, &occupied);
+
+    // Any such path is a line from `a`, a line between the two corners and a line to `b`.
+    from_start.into_iter().flat_map(move |first| {
+        from_end
+            .iter()
+            .filter(|second| clear(first, **second, &occupied))
+            .map(|second| {
+                let mut corners = vec![start, first, *second, end];
+                corners.dedup();
+                corners
+            })
+            .collect::<Vec<_>>()
+    })
+}
+
+/// Whether a path connects `a` and `b`, see [paths].
+pub fn connected(
+    a: &tile::Position,
+    b: &tile::Position,
+    board: &Board,
+) -> bool {
+    paths(a, b, board).next().is_some()
+}
+
+/// Shortest of [paths].
+pub fn path(
+    a: &tile::Position,
+    b: &tile::Position,
+    board: &Board,
+) -> Option<Vec<IVec2>> {
+    paths(a, b, board).min_by_key(|corners| {
+        corners
+            .windows(2)
+            .map(|line| (line[1] - line[0]).abs().element_sum())
+            .sum::<i32>()
+    })
+}
+
+/// Shisen-Sho: matching tiles are removed when a [path] connects them.
+pub struct ShisenSho;
+
+impl RuleSet for ShisenSho {
+    fn key(&self) -> &'static str {
+        "mode.shisen_sho"
+    }
+
+    /// A tile enclosed on all four sides can not be connected to anything.
+    fn is_free(
+        &self,
+        position: &tile::Position,
+        board: &Board,
+    ) -> bool {
+        DIRECTIONS.iter().any(|direction| {
+            let next = cell(position) + direction;
+            !on_board(next) || !board.contains(&self::position(next))
+        })
+    }
+
+    fn valid_removal(
+        &self,
+        a: (&tile::Variant, &tile::Position),
+        b: (&tile::Variant, &tile::Position),
+        board: &Board,
+    ) -> bool {
+        a.0.matches(b.0) && connected(a.1, b.1, board)
+    }
+
+    fn hint(
+        &self,
+        selected: (&tile::Variant, &tile::Position),
+        candidate: (&tile::Variant, &tile::Position),
+        board: &Board,
+    ) -> bool {
+        self.valid_removal(selected, candidate, board)
+    }
+
+    fn moves(
+        &self,
+        free: &[(tile::Variant, tile::Position)],
+        board: &Board,
+    ) -> u32 {
+        let mut moves = 0;
+
+        for (index, a) in free.iter().enumerate() {
+            for b in &free[index + 1..] {
+                if self.valid_removal((&a.0, &a.1), (&b.0, &b.1), board) {
+                    moves += 1;
+                }
+            }
+        }
+
+        moves
+    }
+}
+
+/// Returns a [Vec] with (position, variant) tuples along with the rng seed ([u64]) to create them.
+///
+/// Tiles are dealt at random. A deal is only kept if one of a few random playthroughs clears it.
+pub fn generate_board(seed: Option<u64>) -> (Vec<(tile::Position, tile::Variant)>, u64) {
+    let mut seed = seed.unwrap_or(rand::random());
+
+    loop {
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        let mut variants: Vec<tile::Variant> = tile::Variant::deal(TILES)
+            .into_iter()
+            .flat_map(|(v0, v1)| [v0, v1])
+            .collect();
+        variants.shuffle(&mut rng);
+
+        let board: Vec<(tile::Position, tile::Variant)> = (0..ROWS)
+            .flat_map(|row| (0..COLUMNS).map(move |column| position(IVec2::new(column, row))))
+            .zip(variants)
+            .collect();
+
+        if (0..ATTEMPTS).any(|_| solvable(board.clone(), &mut rng)) {
+            return (board, seed);
+        }
+
+        seed = seed.wrapping_add(1);
+    }
+}
+
+/// Plays randomly chosen pairs until the board is cleared or no pair is left.
+fn solvable(
+    mut board: Vec<(tile::Position, tile::Variant)>,
+    rng: &mut StdRng,
+) -> bool {
+    while !board.is_empty() {
+        let positions: Board = board.iter().map(|(position, _)| *position).collect();
+        let mut pairs: Vec<(usize, usize)> = (0..board.len())
+            .flat_map(|a| (a + 1..board.len()).map(move |b| (a, b)))
+            .filter(|&(a, b)| board[a].1.matches(&board[b].1))
+            .collect();
+        pairs.shuffle(rng);
+
+        let Some((a, b)) = pairs
+            .into_iter()
+            .find(|&(a, b)| connected(&board[a].0, &board[b].0, &positions))
+        else {
+            return false;
+        };
+
+        board.swap_remove(b);
+        board.swap_remove(a);
+    }
+
+    true
+}
+
+/// Line segment of the path connecting the last removed pair. Fades out, then despawns.
+#[derive(Component, Deref, DerefMut)]
+struct Path(Timer);
+
+/// Thickness of a path, as a fraction of the tile width.
+const PATH_WIDTH: f32 = 0.08;
+
+fn draw_path(
+    mut commands: Commands,
+    mut pairs_removed: MessageReader<PairRemoved>,
+    projection: Query<&Projection, With<Camera>>,
+    index: Res<BoardIndex>,
+    rules: Res<Rules>,
+    palette: Res<highlight::Palette>,
+    theme: theme::Active,
+) {
+    if rules.mode() != Mode::ShisenSho || !theme.is_loaded() {
+        pairs_removed.clear();
+        return;
+    }
+
+    let Some(Projection::Orthographic(projection)) = projection.iter().next() else {
+        panic!();
+    };
+
+    let geometry = tile::Geometry::new(projection, &theme.get().tile);
+    let origin = geometry.translation(&position(IVec2::ZERO)) + geometry.face_offset();
+    let world = |cell: IVec2| origin.truncate() + cell.as_vec2() * geometry.size;
+    let width = geometry.size.x * PATH_WIDTH;
+    let color = palette.color(highlight::Highlight::Selected);
+
+    for PairRemoved(player, a, b) in pairs_removed.read() {
+        let Some(corners) = path(a, b, index.get(*player)) else {
+            continue;
+        };
+
+        for line in corners.windows(2) {
+            let (from, to) = (world(line[0]), world(line[1]));
+
+            commands.spawn((
+                Path(Timer::new(Duration::from_millis(400), TimerMode::Once)),
+                DespawnOnExit(InGame::Running),
+                Sprite::from_color(color, (to - from).abs() + Vec2::splat(width)),
+                Transform::from_translation(((from + to) / 2.0).extend(1000.0)),
+                Pickable::IGNORE,
+                player.render_layers(),
+            ));
+        }
+    }
+}
+
+fn fade_path(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut paths: Query<(Entity, &mut Path, &mut Sprite)>,
+    mut frame_budget: ResMut<FrameBudget>,
+) {
+    for (entity, mut path, mut sprite) in &mut paths {
+        frame_budget.request("path");
+
+        if path.tick(time.delta()).is_finished() {
+            commands.entity(entity).despawn();
+        } else {
+            sprite.color.set_alpha(path.fraction_remaining());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Board of tiles at `cells`.
+    fn board(cells: &[IVec2]) -> Board {
+        cells.iter().map(|cell| position(*cell)).collect()
+    }
+
+    /// Full board, but for the cells in `empty`.
+    fn full_but(empty: &[IVec2]) -> Board {
+        (0..ROWS)
+            .flat_map(|row| (0..COLUMNS).map(move |column| IVec2::new(column, row)))
+            .filter(|cell| !empty.contains(cell))
+            .map(position)
+            .collect()
+    }
+
+    /// Corners of the shortest path between the tiles at `a` and `b`.
+    fn corners(
+        a: IVec2,
+        b: IVec2,
+        board: &Board,
+    ) -> Option<Vec<IVec2>> {
+        path(&position(a), &position(b), board)
+    }
+
+    #[test]
+    fn clear_needs_a_line_without_tiles() {
+        let occupied: HashSet<IVec2> = [IVec2::new(2, 0)].into_iter().collect();
+
+        assert!(clear(IVec2::new(0, 0), IVec2::new(0, 3), &occupied));
+        assert!(clear(IVec2::new(3, 0), IVec2::new(5, 0), &occupied));
+        assert!(!clear(IVec2::new(0, 0), IVec2::new(4, 0), &occupied));
+        assert!(!clear(IVec2::new(0, 0), IVec2::new(1, 1), &occupied));
+    }
+
+    #[test]
+    fn paths_turn_at_most_twice() {
+        let (a, b) = (IVec2::new(2, 2), IVec2::new(5, 2));
+
+        // Straight.
+        assert_eq!(corners(a, b, &board(&[a, b])), Some(vec![a, b]));
+
+        // One turn.
+        let c = IVec2::new(5, 4);
+        assert_eq!(
+            corners(a, c, &board(&[a, c])).map(|path| path.len()),
+            Some(3)
+        );
+
+        // Two turns, around a tile in the way.
+        let blocked = board(&[a, IVec2::new(3, 2), b]);
+        assert_eq!(corners(a, b, &blocked).map(|path| path.len()), Some(4));
+        assert!(paths(&position(a), &position(b), &blocked).all(|path| path.len() == 4));
+    }
+
+    #[test]
+    fn paths_go_around_the_edge() {
+        let (a, b) = (IVec2::new(0, 0), IVec2::new(COLUMNS - 1, 0));
+        let board = full_but(&[]);
+
+        assert_eq!(
+            corners(a, b, &board),
+            Some(vec![a, IVec2::new(0, -1), IVec2::new(COLUMNS - 1, -1), b])
+        );
+    }
+
+    #[test]
+    fn three_turns_do_not_connect() {
+        // The only cells left empty, the rest of the board is full.
+        // a .
+        //   . .
+        //     b
+        let (a, b) = (IVec2::new(1, 1), IVec2::new(3, 3));
+        let board = full_but(&[IVec2::new(2, 1), IVec2::new(2, 2), IVec2::new(3, 2)]);
+
+        assert!(!connected(&position(a), &position(b), &board));
+    }
+
+    #[test]
+    fn tiles_in_the_way_do_not_connect() {
+        let (a, b) = (IVec2::new(4, 3), IVec2::new(6, 3));
+        let board = full_but(&[]);
+
+        assert!(!connected(&position(a), &position(b), &board));
+
+        let board = full_but(&[IVec2::new(5, 3)]);
+
+        assert!(connected(&position(a), &position(b), &board));
+    }
+}
@@ -0,0 +1,117 @@
+use crate::plugin::{default::OverlayCamera, locale::Tr};
+use bevy::prelude::*;
+use std::{collections::HashMap, time::Duration};
+
+use super::{
+    InGame, hud,
+    rules::{self, Progress, Rules},
+};
+
+pub struct Plugin;
+
+impl bevy::prelude::Plugin for Plugin {
+    fn build(
+        &self,
+        app: &mut App,
+    ) {
+        app.init_resource::<Stats>()
+            .add_systems(OnEnter(InGame::Running), played)
+            .add_systems(
+                OnEnter(InGame::Victory),
+                (won, spawn_summary::<true>).chain(),
+            )
+            .add_systems(OnEnter(InGame::Defeat), spawn_summary::<false>);
+    }
+}
+
+/// Results of one [rules::RuleSet].
+#[derive(Default, Debug)]
+pub struct Record {
+    pub played: u32,
+    pub won: u32,
+    pub best_time: Option<Duration>,
+}
+
+/// Results of this session, per [rules::RuleSet::key].
+#[derive(Resource, Default, Deref, DerefMut, Debug)]
+pub struct Stats(HashMap<&'static str, Record>);
+
+fn played(
+    mut stats: ResMut<Stats>,
+    rules: Res<Rules>,
+) {
+    stats.entry(rules.get().key()).or_default().played += 1;
+}
+
+fn won(
+    mut stats: ResMut<Stats>,
+    rules: Res<Rules>,
+    progress: Res<Progress>,
+) {
+    let record = stats.entry(rules.get().key()).or_default();
+    record.won += 1;
+    record.best_time = Some(
+        record
+            .best_time
+            .map_or(progress.elapsed, |best| best.min(progress.elapsed)),
+    );
+}
+
+fn spawn_summary<const VICTORY: bool>(
+    mut commands: Commands,
+    stats: Res<Stats>,
+    rules: Res<Rules>,
+    progress: Res<Progress>,
+    camera: Single<Entity, With<OverlayCamera>>,
+    tr: Tr,
+) {
+    let key = rules.get().key();
+    let Some(record) = stats.get(key) else {
+        return;
+    };
+
+    let mut lines = vec![
+        tr.get(key),
+        format!(
+            "{} {}",
+            tr.get("stats.time"),
+            rules::clock(progress.elapsed)
+        ),
+        format!("{} {}", tr.get("stats.played"), record.played),
+        format!("{} {}", tr.get("stats.won"), record.won),
+    ];
+
+    if let Some(best_time) = record.best_time {
+        lines.push(format!(
+            "{} {}",
+            tr.get("stats.best_time"),
+            rules::clock(best_time)
+        ));
+    }
+
+    let state = if VICTORY {
+        InGame::Victory
+    } else {
+        InGame::Defeat
+    };
+
+    commands.spawn((
+        DespawnOnExit(state),
+        UiTargetCamera(*camera),
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::End,
+            padding: UiRect::bottom(Val::Percent(5.0)),
+            ..default()
+        },
+        Pickable::IGNORE,
+        children![(
+            Text::new(lines.join("\n")),
+            TextLayout::new_with_justify(Justify::Center),
+            hud::font(hud::element_size().y / 4.0),
+            Pickable::IGNORE,
+        )],
+    ));
+}
@@ -0,0 +1,296 @@
+use crate::plugin::scene::{
+    in_game::atlas::FaceAtlas,
+    loading::Manifest,
+    main_menu::settings::{AppExt, Setting},
+};
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader},
+    ecs::system::SystemParam,
+    prelude::*,
+};
+use serde::Deserialize;
+use thiserror::Error;
+
+pub mod asset {
+    pub const THEMES: [&str; 3] = [
+        "theme/warcraft.theme.ron",
+        "theme/classic.theme.ron",
+        "theme/high_contrast.theme.ron",
+    ];
+}
+
+pub struct Plugin;
+
+impl bevy::prelude::Plugin for Plugin {
+    fn build(
+        &self,
+        app: &mut App,
+    ) {
+        app.init_asset::<Theme>()
+            .init_asset_loader::<ThemeLoader>()
+            .init_resource::<Themes>()
+            .add_systems(Update, preload)
+            .add_setting(Setting {
+                label: "settings.theme",
+                value: |world| {
+                    let themes = world.resource::<Themes>();
+                    world
+                        .resource::<Assets<Theme>>()
+                        .get(themes.active())
+                        .map(|theme| theme.name.clone())
+                        .unwrap_or_default()
+                },
+                adjust: |world, delta| world.resource_mut::<Themes>().step(delta),
+            });
+    }
+}
+
+/// Describes the look of the board: the tile body, the face of every [super::tile::Variant] and
+/// the background. Loaded from `*.theme.ron` files.
+#[derive(Asset, TypePath, Deserialize, Debug)]
+pub struct Theme {
+    pub name: String,
+    pub tile: TileBody,
+    pub background: Background,
+    /// Indexed by [super::tile::Variant].
+    pub faces: Vec<Face>,
+}
+
+impl Theme {
+    /// Message key of the name of the face of `variant`, e.g. for screen readers.
+    pub fn face_key(
+        &self,
+        variant: super::tile::Variant,
+    ) -> &str {
+        self.faces
+            .get(variant.0 as usize)
+            .map(|face| face.name.as_str())
+            .unwrap_or("face.unknown")
+    }
+
+    /// Paths of every image used by the theme.
+    pub fn images(&self) -> impl Iterator<Item = &str> {
+        let faces = self.faces.iter().filter_map(|face| match &face.art {
+            Art::Image { image, .. } | Art::Pips { image, .. } => Some(image.as_str()),
+            Art::Atlas { .. } | Art::Text { .. } => None,
+        });
+
+        [
+            Some(self.tile.texture.as_str()),
+            self.background.texture.as_deref(),
+        ]
+        .into_iter()
+        .flatten()
+        .chain(faces)
+    }
+
+    /// Paths of every [super::atlas::Atlas] used by the theme.
+    pub fn atlases(&self) -> impl Iterator<Item = &str> {
+        self.faces.iter().filter_map(|face| match &face.art {
+            Art::Atlas { atlas, .. } => Some(atlas.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Whether every image and atlas of the theme is done loading, see [Manifest::is_loaded].
+    pub fn is_preloaded(
+        &self,
+        manifest: &Manifest,
+    ) -> bool {
+        self.images().all(|path| manifest.is_loaded(path))
+            && self.atlases().all(|path| manifest.is_atlas_loaded(path))
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct TileBody {
+    pub texture: String,
+    /// Size of the whole texture (pixels), including the border which gives the tile its depth.
+    pub size: UVec2,
+    /// Size of the front of the tile (pixels), i.e. [TileBody::size] without the border.
+    pub face_size: UVec2,
+    pub border_length: u32,
+    pub color: Color,
+    pub shadow_color: Color,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Background {
+    /// Plain [Background::color] if [None].
+    pub texture: Option<String>,
+    pub color: Color,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Face {
+    /// Message key of the name, see [Theme::face_key].
+    pub name: String,
+    pub art: Art,
+}
+
+/// Sizes and positions are fractions of the tile's face.
+#[derive(Deserialize, Debug)]
+pub enum Art {
+    Image {
+        image: String,
+        size: f32,
+    },
+    /// Copies of the same image, e.g. the 1-9 patterns of a suit.
+    Pips {
+        image: String,
+        pips: Vec<Pip>,
+    },
+    /// A cell of a ready-made sheet of faces, see [super::atlas::Atlas].
+    Atlas {
+        atlas: String,
+        index: usize,
+        size: f32,
+    },
+    /// For faces without any art.
+    Text {
+        text: String,
+        size: f32,
+        color: Color,
+    },
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Pip {
+    pub x: f32,
+    pub y: f32,
+    pub size: f32,
+    #[serde(default)]
+    pub color: Option<Color>,
+}
+
+#[derive(Default, TypePath)]
+pub struct ThemeLoader;
+
+#[derive(Debug, Error)]
+pub enum ThemeLoaderError {
+    #[error("Could not read theme: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not parse theme: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+}
+
+impl AssetLoader for ThemeLoader {
+    type Asset = Theme;
+    type Settings = ();
+    type Error = ThemeLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["theme.ron"]
+    }
+}
+
+/// All bundled themes along with the one currently in use.
+#[derive(Resource)]
+pub struct Themes {
+    handles: Vec<Handle<Theme>>,
+    active: usize,
+}
+
+impl FromWorld for Themes {
+    fn from_world(world: &mut World) -> Self {
+        let asset_server = world.resource::<AssetServer>();
+
+        Self {
+            handles: asset::THEMES
+                .iter()
+                .map(|path| asset_server.load(*path))
+                .collect(),
+            active: 0,
+        }
+    }
+}
+
+impl Themes {
+    pub fn active(&self) -> &Handle<Theme> {
+        &self.handles[self.active]
+    }
+
+    pub fn step(
+        &mut self,
+        delta: i32,
+    ) {
+        self.active = (self.active as i32 + delta).rem_euclid(self.handles.len() as i32) as usize;
+    }
+}
+
+/// The active [Theme]. Only use [Active::get] once [Active::is_loaded], which holds from
+/// `InGame::Init` onwards.
+#[derive(SystemParam)]
+pub struct Active<'w> {
+    themes: Res<'w, Themes>,
+    assets: Res<'w, Assets<Theme>>,
+    manifest: Res<'w, Manifest>,
+    faces: Res<'w, FaceAtlas>,
+}
+
+impl Active<'_> {
+    pub fn id(&self) -> AssetId<Theme> {
+        self.themes.active().id()
+    }
+
+    /// Whether the theme, and every image it uses, is loaded and its faces are baked. A newly
+    /// selected theme only takes effect once it is, rather than have its images pop in.
+    pub fn is_loaded(&self) -> bool {
+        self.assets
+            .get(self.id())
+            .is_some_and(|theme| theme.is_preloaded(&self.manifest))
+            && self.faces.is_baked(self.id())
+    }
+
+    pub fn get(&self) -> &Theme {
+        self.assets.get(self.id()).expect("Theme not loaded!")
+    }
+}
+
+pub fn select_keyboard(
+    key: Res<ButtonInput<KeyCode>>,
+    mut themes: ResMut<Themes>,
+) {
+    if key.just_pressed(KeyCode::KeyT) {
+        themes.step(1);
+    }
+}
+
+/// Adds the images and atlases of the active theme to the [Manifest] as soon as it is loaded, including after
+/// switching themes or editing the active one.
+fn preload(
+    themes: Res<Themes>,
+    assets: Res<Assets<Theme>>,
+    mut theme_events: MessageReader<AssetEvent<Theme>>,
+    mut manifest: ResMut<Manifest>,
+) {
+    let id = themes.active().id();
+    let changed = theme_events
+        .read()
+        .any(|event| event.is_loaded_with_dependencies(id) || event.is_modified(id));
+
+    if !(changed || themes.is_changed()) {
+        return;
+    }
+
+    if let Some(theme) = assets.get(id) {
+        for path in theme.images() {
+            manifest.preload(path);
+        }
+
+        for path in theme.atlases() {
+            manifest.preload_atlas(path);
+        }
+    }
+}
@@ -0,0 +1,252 @@
+use crate::plugin::{
+    default::OverlayCamera,
+    frame_budget::FrameBudget,
+    locale::Locale,
+    scene::{
+        Startup,
+        in_game::{atlas::Atlas, theme},
+    },
+};
+use bevy::{
+    asset::{LoadState, RecursiveDependencyLoadState, UntypedAssetId},
+    platform::collections::HashMap,
+    prelude::*,
+};
+
+pub mod asset {
+    pub const GREETER: &str = "misc/rev2/original/Greeter.png";
+    pub const VICTORY: &str = "misc/rev2/original/Victory.png";
+    pub const DEFEAT: &str = "misc/rev2/original/Defeat.png";
+    pub const BUTTON: &str = "misc/rev2/button-atlas.stitch.ron";
+    pub const INFO: &str = "misc/rev2/StoneSlab.png";
+}
+
+pub struct Plugin;
+
+impl bevy::prelude::Plugin for Plugin {
+    fn build(
+        &self,
+        app: &mut App,
+    ) {
+        app.init_resource::<Manifest>()
+            .add_systems(OnEnter(Startup::Loading), spawn)
+            .add_systems(Update, progress.run_if(in_state(Startup::Loading)));
+    }
+}
+
+/// Handles of every image the game shows, loaded up front so that nothing pops in. The images and
+/// atlases of a [theme::Theme] are added by [Manifest::preload] and [Manifest::preload_atlas] once
+/// it is selected, see [theme::Active::is_loaded].
+#[derive(Resource)]
+pub struct Manifest {
+    pub greeter: Handle<Image>,
+    pub victory: Handle<Image>,
+    pub defeat: Handle<Image>,
+    pub button: Handle<Image>,
+    pub info: Handle<Image>,
+    /// Images of themes, by path.
+    images: HashMap<String, Handle<Image>>,
+    /// Atlases of themes, by path.
+    atlases: HashMap<String, Handle<Atlas>>,
+    asset_server: AssetServer,
+}
+
+impl FromWorld for Manifest {
+    fn from_world(world: &mut World) -> Self {
+        let asset_server = world.resource::<AssetServer>().clone();
+
+        Self {
+            greeter: asset_server.load(asset::GREETER),
+            victory: asset_server.load(asset::VICTORY),
+            defeat: asset_server.load(asset::DEFEAT),
+            button: asset_server.load(asset::BUTTON),
+            info: asset_server.load(asset::INFO),
+            images: HashMap::new(),
+            atlases: HashMap::new(),
+            asset_server,
+        }
+    }
+}
+
+impl Manifest {
+    /// Starts loading the image at `path`, unless it already is.
+    pub fn preload(
+        &mut self,
+        path: &str,
+    ) {
+        if !self.images.contains_key(path) {
+            self.images
+                .insert(path.to_string(), self.asset_server.load(path.to_string()));
+        }
+    }
+
+    /// Image at `path`, which should have been preloaded.
+    pub fn image(
+        &self,
+        path: &str,
+    ) -> Handle<Image> {
+        self.images.get(path).cloned().unwrap_or_else(|| {
+            warn!("Image \"{path}\" was not preloaded!");
+            self.asset_server.load(path.to_string())
+        })
+    }
+
+    /// Whether the image at `path` was preloaded and is done loading. Images which failed to load
+    /// count as done, so that a missing file can not hold up the game.
+    pub fn is_loaded(
+        &self,
+        path: &str,
+    ) -> bool {
+        self.images
+            .get(path)
+            .is_some_and(|handle| self.is_settled(handle.id().untyped()))
+    }
+
+    /// Starts loading the atlas at `path`, along with its image, unless it already is.
+    pub fn preload_atlas(
+        &mut self,
+        path: &str,
+    ) {
+        if !self.atlases.contains_key(path) {
+            self.atlases
+                .insert(path.to_string(), self.asset_server.load(path.to_string()));
+        }
+    }
+
+    /// Atlas at `path`, which should have been preloaded.
+    pub fn atlas(
+        &self,
+        path: &str,
+    ) -> Handle<Atlas> {
+        self.atlases.get(path).cloned().unwrap_or_else(|| {
+            warn!("Atlas \"{path}\" was not preloaded!");
+            self.asset_server.load(path.to_string())
+        })
+    }
+
+    /// Like [Manifest::is_loaded], for the atlas at `path` and its image.
+    pub fn is_atlas_loaded(
+        &self,
+        path: &str,
+    ) -> bool {
+        self.atlases.get(path).is_some_and(|handle| {
+            matches!(
+                self.asset_server
+                    .get_recursive_dependency_load_state(handle),
+                Some(
+                    RecursiveDependencyLoadState::Loaded | RecursiveDependencyLoadState::Failed(_)
+                )
+            )
+        })
+    }
+
+    fn is_settled(
+        &self,
+        id: UntypedAssetId,
+    ) -> bool {
+        matches!(
+            self.asset_server.get_load_state(id),
+            Some(LoadState::Loaded | LoadState::Failed(_))
+        )
+    }
+
+    fn ids(&self) -> impl Iterator<Item = UntypedAssetId> {
+        [
+            &self.greeter,
+            &self.victory,
+            &self.defeat,
+            &self.button,
+            &self.info,
+        ]
+        .into_iter()
+        .chain(self.images.values())
+        .map(|handle| handle.id().untyped())
+        .chain(self.atlases.values().map(|handle| handle.id().untyped()))
+    }
+}
+
+/// Filled up to the fraction of assets loaded.
+#[derive(Component)]
+struct Bar;
+
+#[derive(Component)]
+struct Percent;
+
+fn spawn(
+    mut commands: Commands,
+    camera: Single<Entity, With<OverlayCamera>>,
+) {
+    commands.spawn((
+        DespawnOnExit(Startup::Loading),
+        UiTargetCamera(*camera),
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            flex_direction: FlexDirection::Column,
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(16.0),
+            ..default()
+        },
+        BackgroundColor(Color::srgb(0.1, 0.1, 0.1)),
+        children![
+            (
+                Node {
+                    width: Val::Percent(40.0),
+                    height: Val::Px(16.0),
+                    border_radius: BorderRadius::all(Val::Px(8.0)),
+                    overflow: Overflow::clip(),
+                    ..default()
+                },
+                BackgroundColor(Color::srgb(0.25, 0.25, 0.25)),
+                children![(
+                    Bar,
+                    Node {
+                        width: Val::Percent(0.0),
+                        height: Val::Percent(100.0),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgb(0.9, 0.9, 0.9)),
+                )],
+            ),
+            (
+                Percent,
+                Text::new("0%"),
+                TextFont {
+                    font_size: 24.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.9, 0.9, 0.9)),
+            ),
+        ],
+    ));
+}
+
+/// Moves on to the greeter once the [Manifest], the active catalog and the active theme (along
+/// with its images) are loaded.
+fn progress(
+    manifest: Res<Manifest>,
+    locale: Res<Locale>,
+    theme: theme::Active,
+    mut bar: Single<&mut Node, With<Bar>>,
+    mut percent: Single<&mut Text, With<Percent>>,
+    mut next_state: ResMut<NextState<Startup>>,
+    mut frame_budget: ResMut<FrameBudget>,
+) {
+    // Assets finishing to load do not wake the app up.
+    frame_budget.request("loading");
+
+    let ids: Vec<UntypedAssetId> = manifest
+        .ids()
+        .chain([locale.active().id().untyped(), theme.id().untyped()])
+        .collect();
+    let loaded = ids.iter().filter(|id| manifest.is_settled(**id)).count();
+    let fraction = loaded as f32 / ids.len() as f32;
+
+    bar.width = Val::Percent(fraction * 100.0);
+    percent.0 = format!("{:.0}%", fraction * 100.0);
+
+    if loaded == ids.len() && theme.is_loaded() {
+        next_state.set(Startup::Greeter);
+    }
+}
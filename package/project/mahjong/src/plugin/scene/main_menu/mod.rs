@@ -1,5 +1,5 @@
 mod about;
-mod settings;
+pub mod settings;
 
 use crate::plugin::scene::Startup;
 use crate::plugin::scene::in_game;
@@ -12,7 +12,8 @@ impl bevy::prelude::Plugin for Plugin {
         &self,
         app: &mut App,
     ) {
-        app.add_sub_state::<MainMenu>().add_plugins(in_game::Plugin);
+        app.add_sub_state::<MainMenu>()
+            .add_plugins((in_game::Plugin, settings::Plugin));
         // .add_systems(OnEnter(MainMenu::Root), on_enter)
         // .add_systems(Update, update.run_if(in_state(MainMenu::Root)))
         // .add_plugins((in_game::Plugin, about::Plugin));
@@ -0,0 +1,226 @@
+use crate::plugin::{default::OverlayCamera, locale::Localized, scene::Startup};
+use bevy::prelude::*;
+
+pub struct Plugin;
+
+impl bevy::prelude::Plugin for Plugin {
+    fn build(
+        &self,
+        app: &mut App,
+    ) {
+        app.init_resource::<Settings>()
+            .insert_resource(Open(false))
+            .add_systems(
+                Update,
+                (
+                    toggle_keyboard,
+                    spawn.run_if(resource_changed::<Open>),
+                    refresh.run_if(resource_equals(Open(true))),
+                )
+                    .chain()
+                    .run_if(in_state(Startup::MainMenu)),
+            );
+    }
+}
+
+/// A single row of the settings panel, registered through [AppExt::add_setting].
+pub struct Setting {
+    /// Message key, see [crate::plugin::locale].
+    pub label: &'static str,
+    /// Current value, as displayed.
+    pub value: fn(&World) -> String,
+    /// Called with `-1` or `1` when the corresponding step button is pressed.
+    pub adjust: fn(&mut World, i32),
+}
+
+/// All registered [Setting]s, in display order.
+#[derive(Resource, Default)]
+pub struct Settings(Vec<Setting>);
+
+pub trait AppExt {
+    fn add_setting(
+        &mut self,
+        setting: Setting,
+    ) -> &mut Self;
+}
+
+impl AppExt for App {
+    fn add_setting(
+        &mut self,
+        setting: Setting,
+    ) -> &mut Self {
+        self.init_resource::<Settings>();
+        self.world_mut().resource_mut::<Settings>().0.push(setting);
+        self
+    }
+}
+
+/// Whether the settings panel is shown.
+#[derive(Resource, PartialEq, Eq)]
+pub struct Open(pub bool);
+
+#[derive(Component)]
+struct Panel;
+
+/// Index into [Settings].
+#[derive(Component)]
+struct Value(usize);
+
+#[derive(Component)]
+struct Step {
+    index: usize,
+    delta: i32,
+}
+
+fn font(font_size: f32) -> impl Bundle + Clone {
+    (
+        TextFont {
+            font_size,
+            ..default()
+        },
+        TextColor(Color::srgb(0.9, 0.9, 0.9)),
+    )
+}
+
+fn toggle_keyboard(
+    key: Res<ButtonInput<KeyCode>>,
+    mut open: ResMut<Open>,
+) {
+    if key.just_pressed(KeyCode::Escape) {
+        open.0 = !open.0;
+    }
+}
+
+fn spawn(
+    mut commands: Commands,
+    open: Res<Open>,
+    settings: Res<Settings>,
+    panels: Query<Entity, With<Panel>>,
+    camera: Single<Entity, With<OverlayCamera>>,
+) {
+    for panel in panels {
+        commands.entity(panel).despawn();
+    }
+
+    if !open.0 {
+        return;
+    }
+
+    let button = (
+        Button,
+        Node {
+            width: Val::Px(48.0),
+            justify_content: JustifyContent::Center,
+            border_radius: BorderRadius::all(Val::Px(8.0)),
+            ..default()
+        },
+        BackgroundColor(Color::srgb(0.3, 0.3, 0.3)),
+    );
+
+    commands
+        .spawn((
+            Panel,
+            DespawnOnExit(Startup::MainMenu),
+            UiTargetCamera(*camera),
+            GlobalZIndex(i32::MAX),
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Node {
+                        flex_direction: FlexDirection::Column,
+                        row_gap: Val::Px(16.0),
+                        padding: UiRect::all(Val::Px(32.0)),
+                        border_radius: BorderRadius::all(Val::Px(16.0)),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
+                ))
+                .with_children(|column| {
+                    column.spawn((Localized("settings.title"), Text::default(), font(48.0)));
+
+                    for (index, setting) in settings.0.iter().enumerate() {
+                        column
+                            .spawn(Node {
+                                column_gap: Val::Px(16.0),
+                                align_items: AlignItems::Center,
+                                ..default()
+                            })
+                            .with_children(|row| {
+                                row.spawn((
+                                    Localized(setting.label),
+                                    Text::default(),
+                                    font(32.0),
+                                    Node {
+                                        width: Val::Px(320.0),
+                                        ..default()
+                                    },
+                                ));
+
+                                let step_button = |delta: i32, label: &'static str| {
+                                    (
+                                        button.clone(),
+                                        Step { index, delta },
+                                        children![(Text::new(label), font(32.0), Pickable::IGNORE)],
+                                    )
+                                };
+
+                                row.spawn(step_button(-1, "-")).observe(step);
+                                row.spawn((
+                                    Value(index),
+                                    Text::default(),
+                                    font(32.0),
+                                    TextLayout::new_with_justify(Justify::Center),
+                                    Node {
+                                        width: Val::Px(120.0),
+                                        ..default()
+                                    },
+                                ));
+                                row.spawn(step_button(1, "+")).observe(step);
+                            });
+                    }
+
+                    column.spawn((Localized("settings.close"), Text::default(), font(24.0)));
+                });
+        });
+}
+
+fn step(
+    on_press: On<Pointer<Press>>,
+    steps: Query<&Step>,
+    mut commands: Commands,
+) {
+    let Ok(&Step { index, delta }) = steps.get(on_press.entity) else {
+        return;
+    };
+
+    commands.queue(move |world: &mut World| {
+        let adjust = world.resource::<Settings>().0[index].adjust;
+        adjust(world, delta);
+    });
+}
+
+fn refresh(world: &mut World) {
+    let mut values = world.query::<(Entity, &Value)>();
+    let settings = world.resource::<Settings>();
+    let updates: Vec<(Entity, String)> = values
+        .iter(world)
+        .map(|(entity, value)| (entity, (settings.0[value.0].value)(world)))
+        .collect();
+
+    for (entity, value) in updates {
+        if let Some(mut text) = world.get_mut::<Text>(entity)
+            && text.0 != value
+        {
+            text.0 = value;
+        }
+    }
+}
@@ -1,11 +1,9 @@
 mod greeter;
-mod in_game;
-mod main_menu;
+pub mod in_game;
+pub mod loading;
+pub mod main_menu;
 
-use bevy::{
-    diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin},
-    prelude::*,
-};
+use bevy::prelude::*;
 
 pub struct Plugin;
 
@@ -15,11 +13,8 @@ impl bevy::prelude::Plugin for Plugin {
         app: &mut App,
     ) {
         app.init_state::<Startup>()
-            .insert_resource(FpsToggle(false))
-            .insert_resource(MyTimer(Timer::from_seconds(1.0, TimerMode::Repeating)))
             .add_systems(Startup, startup)
-            .add_systems(Update, print_fps)
-            .add_plugins((greeter::Plugin, main_menu::Plugin));
+            .add_plugins((loading::Plugin, greeter::Plugin, main_menu::Plugin));
     }
 }
 
@@ -28,39 +23,12 @@ impl bevy::prelude::Plugin for Plugin {
 pub enum Startup {
     #[default]
     Root,
+    Loading,
     Greeter,
     MainMenu,
 }
 
-#[derive(Resource)]
-struct MyTimer(Timer);
-
-#[derive(Resource)]
-struct FpsToggle(bool);
-
 fn startup(mut next_state: ResMut<NextState<Startup>>) {
     info!("Initializing...");
-    next_state.set(Startup::Greeter);
-}
-
-fn print_fps(
-    diagnostics: Res<DiagnosticsStore>,
-    time: Res<Time>,
-    mut timer: ResMut<MyTimer>,
-    mut fps_toggle: ResMut<FpsToggle>,
-    button: Res<ButtonInput<KeyCode>>,
-) {
-    if button.just_pressed(KeyCode::KeyF) {
-        fps_toggle.0 = !fps_toggle.0;
-    }
-
-    if !timer.0.tick(time.delta()).just_finished() {
-        return;
-    }
-
-    if fps_toggle.0 && let Some(fps) = diagnostics.get(&FrameTimeDiagnosticsPlugin::FPS) {
-        if let Some(value) = fps.smoothed() {
-            info!("FPS: {}", value);
-        }
-    }
+    next_state.set(Startup::Loading);
 }
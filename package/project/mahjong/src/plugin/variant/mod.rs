@@ -0,0 +1,183 @@
+//! Images come in variants of different resolution, e.g. smaller ones for the web where every byte
+//! is downloaded before the game starts. Variants are made by the `mahjong-assets` binary (see
+//! [tool]) and kept under [DIR], mirroring the paths of the originals. They are kept out of the
+//! asset directory, which the asset processor would otherwise take them as source art of (see
+//! [crate::plugin::process]). The game keeps loading images by their original path:
+//! [Variant::source] swaps in the variant when there is one, and falls back to the original when
+//! there is not.
+
+#[cfg(not(target_arch = "wasm32"))]
+pub mod tool;
+
+use crate::plugin::platform::{Platform, PlatformTrait};
+use bevy::{
+    asset::{
+        AssetMode,
+        io::{
+            AssetReader, AssetReaderError, AssetSource, AssetSourceBuilder, ErasedAssetReader,
+            PathStream, Reader,
+        },
+    },
+    prelude::*,
+};
+use std::path::{Path, PathBuf};
+
+/// Directory of the variants, next to the asset directory. Holds one directory per [Variant].
+pub const DIR: &str = "variant";
+
+/// Extensions of the assets which come in variants. Anything else is always loaded as is, which
+/// also spares the web build a request for a variant that can not exist.
+pub const EXTENSIONS: [&str; 1] = ["png"];
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Variant {
+    /// Downscaled and recompressed, the default on the web.
+    Lowres,
+    /// The images as they are, the default elsewhere.
+    Original,
+}
+
+impl Variant {
+    pub const ALL: [Variant; 2] = [Variant::Lowres, Variant::Original];
+
+    pub fn key(&self) -> &'static str {
+        use Variant::*;
+
+        match self {
+            Lowres => "lowres",
+            Original => "original",
+        }
+    }
+
+    /// The variant asked for by the user (see [PlatformTrait::asset_variant]), or else the default
+    /// of the platform.
+    pub fn select() -> Self {
+        let asked = Platform::default().asset_variant();
+
+        match asked.and_then(|key| Self::ALL.into_iter().find(|variant| variant.key() == key)) {
+            Some(variant) => variant,
+            None if cfg!(target_arch = "wasm32") => Variant::Lowres,
+            None => Variant::Original,
+        }
+    }
+
+    /// Directory of this variant, mirroring the asset directory.
+    pub fn dir(&self) -> PathBuf {
+        Path::new(DIR).join(self.key())
+    }
+
+    /// Whether the asset at `path` (relative to the asset directory) comes in this variant.
+    pub fn applies(
+        &self,
+        path: &Path,
+    ) -> bool {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .unwrap_or_default();
+
+        *self != Variant::Original && EXTENSIONS.contains(&extension)
+    }
+
+    /// The default asset source of `asset_plugin`, reading variants where there are any. Only the
+    /// directory the game loads from is affected, i.e. [AssetPlugin::processed_file_path] in
+    /// [AssetMode::Processed], so that the asset processor keeps reading the source art. Has to be
+    /// registered before [AssetPlugin] is added.
+    ///
+    /// The web bundle already holds its images in the [Variant::Lowres] variant, at their original
+    /// paths (see [tool]), so they are read as is there.
+    pub fn source(
+        self,
+        asset_plugin: &AssetPlugin,
+    ) -> AssetSourceBuilder {
+        let source = AssetSourceBuilder::platform_default(
+            &asset_plugin.file_path,
+            Some(&asset_plugin.processed_file_path),
+        );
+
+        if cfg!(target_arch = "wasm32") && self == Variant::Lowres {
+            return source;
+        }
+
+        let mut variants =
+            AssetSource::get_default_reader(self.dir().to_string_lossy().into_owned());
+
+        match asset_plugin.mode {
+            AssetMode::Unprocessed => {
+                let mut reader = AssetSource::get_default_reader(asset_plugin.file_path.clone());
+
+                source.with_reader(move || {
+                    Box::new(VariantReader {
+                        inner: reader(),
+                        variants: variants(),
+                        variant: self,
+                    })
+                })
+            },
+            AssetMode::Processed => {
+                let mut reader =
+                    AssetSource::get_default_reader(asset_plugin.processed_file_path.clone());
+
+                source.with_processed_reader(move || {
+                    Box::new(VariantReader {
+                        inner: reader(),
+                        variants: variants(),
+                        variant: self,
+                    })
+                })
+            },
+        }
+    }
+}
+
+/// Reads the [Variant] of an asset in place of the asset itself, whenever there is one.
+struct VariantReader {
+    inner: Box<dyn ErasedAssetReader>,
+    /// Reads [Variant::dir].
+    variants: Box<dyn ErasedAssetReader>,
+    variant: Variant,
+}
+
+impl AssetReader for VariantReader {
+    async fn read<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> Result<Box<dyn Reader + 'a>, AssetReaderError> {
+        if self.variant.applies(path) {
+            match self.variants.read(path).await {
+                Ok(reader) => return Ok(reader),
+                Err(AssetReaderError::NotFound(_)) => {
+                    debug!(
+                        "No {} variant of \"{}\"",
+                        self.variant.key(),
+                        path.display()
+                    );
+                },
+                Err(error) => return Err(error),
+            }
+        }
+
+        self.inner.read(path).await
+    }
+
+    async fn read_meta<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> Result<Box<dyn Reader + 'a>, AssetReaderError> {
+        self.inner.read_meta(path).await
+    }
+
+    async fn read_directory<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> Result<Box<PathStream>, AssetReaderError> {
+        self.inner.read_directory(path).await
+    }
+
+    async fn is_directory<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> Result<bool, AssetReaderError> {
+        self.inner.is_directory(path).await
+    }
+}
@@ -0,0 +1,323 @@
+//! Makes a [Variant] of the images the game loads and, for the web build, a bundle of only the
+//! assets it uses. Those are the processed assets (see [crate::plugin::process]), which `--process`
+//! brings up to date first. Run through the `mahjong-assets` binary, from the package directory:
+//!
+//! ```sh
+//! cargo run --release -p mahjong --bin mahjong-assets --features process -- --process --bundle web/asset
+//! ```
+
+use super::{EXTENSIONS, Variant};
+use crate::plugin::process::PROCESSED_DIR;
+use image::{
+    DynamicImage,
+    codecs::png::{CompressionType, FilterType as PngFilterType, PngEncoder},
+    imageops::FilterType,
+};
+use std::{
+    collections::HashSet,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+const USAGE: &str = "\
+Usage: mahjong-assets [OPTIONS]
+
+Options:
+    --process             Process the source art first (needs the process feature)
+    --asset <dir>         Directory of the assets the game loads (default: imported_assets/Default)
+    --source <dir>        Source directory, searched for the assets in use (default: src)
+    --variant <lowres>    Variant to make (default: lowres)
+    --max-size <n>        Longest side of a resized image, in pixels (default: 512)
+    --bundle <dir>        Copy the assets in use to <dir>, images in the variant where there is one";
+
+/// Sources of the game which are never loaded by it.
+const EXCLUDED_EXTENSIONS: [&str; 2] = ["kra", "py"];
+
+struct Options {
+    process: bool,
+    asset: PathBuf,
+    source: PathBuf,
+    variant: Variant,
+    max_size: u32,
+    bundle: Option<PathBuf>,
+}
+
+impl Options {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut options = Options {
+            process: false,
+            asset: PathBuf::from(PROCESSED_DIR),
+            source: PathBuf::from("src"),
+            variant: Variant::Lowres,
+            max_size: 512,
+            bundle: None,
+        };
+
+        while let Some(arg) = args.next() {
+            if arg == "--help" || arg == "-h" {
+                return Err(USAGE.to_string());
+            }
+
+            if arg == "--process" {
+                options.process = true;
+                continue;
+            }
+
+            let value = args
+                .next()
+                .ok_or_else(|| format!("Missing value of {arg}\n\n{USAGE}"))?;
+            let invalid = || format!("Invalid value of {arg}: {value}\n\n{USAGE}");
+
+            match arg.as_str() {
+                "--asset" => options.asset = PathBuf::from(&value),
+                "--source" => options.source = PathBuf::from(&value),
+                "--variant" => {
+                    // The original is what variants are made from.
+                    options.variant = *Variant::ALL
+                        .iter()
+                        .filter(|variant| **variant != Variant::Original)
+                        .find(|variant| variant.key() == value)
+                        .ok_or_else(invalid)?
+                },
+                "--max-size" => options.max_size = value.parse().map_err(|_| invalid())?,
+                "--bundle" => options.bundle = Some(PathBuf::from(&value)),
+                _ => return Err(format!("Unknown option {arg}\n\n{USAGE}")),
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+/// Paths of every file under `dir`, relative to `root`, sorted.
+fn files(
+    root: &Path,
+    dir: &Path,
+) -> io::Result<Vec<PathBuf>> {
+    let mut found = Vec::new();
+
+    for entry in fs::read_dir(root.join(dir))? {
+        let path = dir.join(entry?.file_name());
+
+        if root.join(&path).is_dir() {
+            found.extend(files(root, &path)?);
+        } else {
+            found.push(path);
+        }
+    }
+
+    found.sort();
+    Ok(found)
+}
+
+fn extension(path: &Path) -> &str {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or_default()
+}
+
+fn is_image(path: &Path) -> bool {
+    EXTENSIONS.contains(&extension(path))
+}
+
+/// Krita saves backups as e.g. `x256.bak.kra` or `border~000.kra`.
+fn is_backup(path: &Path) -> bool {
+    let name = path.to_string_lossy();
+    name.contains('~') || name.contains(".bak.")
+}
+
+/// Images mentioned in string literals, e.g. `"theme/classic.theme.ron"` or
+/// `image: "atlas/x256.png"`. Every other part of a file split on quotes is inside quotes.
+fn mentioned(text: &str) -> impl Iterator<Item = PathBuf> + '_ {
+    text.split('"')
+        .skip(1)
+        .step_by(2)
+        .map(PathBuf::from)
+        .filter(|path| is_image(path))
+}
+
+/// Images the game uses: those mentioned by the source code or by any RON asset.
+fn used_images(options: &Options) -> io::Result<HashSet<PathBuf>> {
+    let sources = files(&options.source, Path::new(""))?
+        .into_iter()
+        .filter(|path| extension(path) == "rs")
+        .map(|path| options.source.join(path));
+    let assets = files(&options.asset, Path::new(""))?
+        .into_iter()
+        .filter(|path| extension(path) == "ron")
+        .map(|path| options.asset.join(path));
+
+    let mut used = HashSet::new();
+
+    for path in sources.chain(assets) {
+        // Processed `*.stitch.ron` files are images.
+        used.extend(mentioned(&String::from_utf8_lossy(&fs::read(path)?)));
+    }
+
+    Ok(used)
+}
+
+/// Downscales the image at `source` to fit [Options::max_size], unless it `keep_size`, and
+/// recompresses it. Returns the encoded image, [None] if that is no smaller than the original.
+fn shrink(
+    source: &Path,
+    max_size: u32,
+    keep_size: bool,
+) -> Result<Option<Vec<u8>>, String> {
+    let bytes = fs::read(source).map_err(|error| error.to_string())?;
+    let image = image::load_from_memory(&bytes).map_err(|error| error.to_string())?;
+
+    let resize = !keep_size && image.width().max(image.height()) > max_size;
+    let image: DynamicImage = if resize {
+        image.resize(max_size, max_size, FilterType::Lanczos3)
+    } else {
+        image
+    };
+
+    let mut encoded = Vec::new();
+    image
+        .write_with_encoder(PngEncoder::new_with_quality(
+            &mut encoded,
+            CompressionType::Best,
+            PngFilterType::Adaptive,
+        ))
+        .map_err(|error| error.to_string())?;
+
+    Ok((encoded.len() < bytes.len()).then_some(encoded))
+}
+
+/// Writes the variant of every image, and removes those which are no longer of any use.
+fn make_variants(
+    options: &Options,
+    files: &[PathBuf],
+) -> io::Result<()> {
+    let sheets: HashSet<PathBuf> = files
+        .iter()
+        .filter(|path| path.to_string_lossy().ends_with(".atlas.ron"))
+        .map(|path| fs::read_to_string(options.asset.join(path)))
+        .collect::<io::Result<Vec<String>>>()?
+        .iter()
+        .flat_map(|text| mentioned(text).collect::<Vec<_>>())
+        .collect();
+
+    let (mut before, mut after) = (0, 0);
+
+    for path in files.iter().filter(|path| is_image(path)) {
+        let source = options.asset.join(path);
+        let target = options.variant.dir().join(path);
+        // Sheets are drawn by pixel coordinates, and so must keep their size.
+        let keep_size = sheets.contains(path);
+
+        match shrink(&source, options.max_size, keep_size) {
+            Ok(Some(encoded)) => {
+                let size = fs::metadata(&source)?.len();
+                println!("{}: {size} -> {} bytes", path.display(), encoded.len());
+                before += size;
+                after += encoded.len() as u64;
+
+                fs::create_dir_all(target.parent().expect("Variants are in a directory"))?;
+                fs::write(&target, encoded)?;
+            },
+            Ok(None) => {
+                println!("{}: kept as is", path.display());
+
+                if target.exists() {
+                    fs::remove_file(&target)?;
+                }
+            },
+            // E.g. a Git LFS pointer which was never fetched.
+            Err(error) => eprintln!("{}: skipped, {error}", path.display()),
+        }
+    }
+
+    println!(
+        "{} variant: {before} -> {after} bytes",
+        options.variant.key()
+    );
+
+    Ok(())
+}
+
+/// Copies whatever the game may load to `bundle`: every asset but unused images, backups and the
+/// sources of the art. Images are copied in their variant where there is one, and as is otherwise.
+fn make_bundle(
+    options: &Options,
+    files: &[PathBuf],
+    bundle: &Path,
+) -> io::Result<()> {
+    let used = used_images(options)?;
+
+    if bundle.exists() {
+        fs::remove_dir_all(bundle)?;
+    }
+
+    let mut total = 0;
+
+    for path in files {
+        // Meta files go along with their asset.
+        let asset = if extension(path) == "meta" {
+            path.with_extension("")
+        } else {
+            path.clone()
+        };
+
+        if EXCLUDED_EXTENSIONS.contains(&extension(&asset)) || is_backup(&asset) {
+            continue;
+        }
+
+        if is_image(&asset) && !used.contains(&asset) {
+            println!("{}: unused, left out", path.display());
+            continue;
+        }
+
+        let variant = options.variant.dir().join(path);
+        let source = if options.variant.applies(path) && variant.exists() {
+            variant
+        } else {
+            options.asset.join(path)
+        };
+        let target = bundle.join(path);
+
+        fs::create_dir_all(target.parent().expect("Assets are in a directory"))?;
+        total += fs::copy(source, target)?;
+    }
+
+    println!("{}: {total} bytes", bundle.display());
+
+    Ok(())
+}
+
+/// Entry point of `mahjong-assets`, see [USAGE].
+pub fn main() {
+    let options = match Options::parse(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{message}");
+            std::process::exit(if message == USAGE { 0 } else { 2 });
+        },
+    };
+
+    if options.process {
+        #[cfg(feature = "process")]
+        crate::plugin::process::run();
+        #[cfg(not(feature = "process"))]
+        {
+            eprintln!("--process needs the process feature\n\n{USAGE}");
+            std::process::exit(2);
+        }
+    }
+
+    let result = files(&options.asset, Path::new("")).and_then(|files| {
+        make_variants(&options, &files)?;
+
+        match &options.bundle {
+            Some(bundle) => make_bundle(&options, &files, bundle),
+            None => Ok(()),
+        }
+    });
+
+    if let Err(error) = result {
+        eprintln!("{error}");
+        std::process::exit(1);
+    }
+}
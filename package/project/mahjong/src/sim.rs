@@ -0,0 +1,413 @@
+//! Plays generated deals with [bot] strategies, without a window, and reports how they fared. Run
+//! through the `mahjong-sim` binary:
+//!
+//! ```sh
+//! cargo run --release -p mahjong --bin mahjong-sim -- --deals 100 --format json
+//! ```
+
+use bevy::math::{UVec2, UVec3};
+use rand::{SeedableRng, rngs::StdRng};
+use serde::Serialize;
+use std::{
+    fmt::Write,
+    time::{Duration, Instant},
+};
+
+use crate::plugin::scene::in_game::{bot, generator, memory, rules, shisen, tile};
+
+const USAGE: &str = "\
+Usage: mahjong-sim [OPTIONS]
+
+Options:
+    --layout <turtle|twin_turtle|shisen_sho|memory>
+                                              Layout to deal, repeatable (default: all)
+    --strategy <random|greedy|lookahead>      Strategy to play with, repeatable (default: all)
+    --rules <classic|open|peek|...>           Rules of the turtle layout (default: classic)
+    --seed <n>                                First seed (default: 0)
+    --deals <n>                               Deals per layout, one per seed from the first (default: 100)
+    --format <csv|json>                       Output format (default: csv)";
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Layout {
+    Turtle,
+    /// Two turtles, one above the other, to measure how generation scales past a single set of tiles.
+    TwinTurtle,
+    ShisenSho,
+    Memory,
+}
+
+impl Layout {
+    const ALL: [Layout; 4] = [
+        Layout::Turtle,
+        Layout::TwinTurtle,
+        Layout::ShisenSho,
+        Layout::Memory,
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Layout::Turtle => "turtle",
+            Layout::TwinTurtle => "twin_turtle",
+            Layout::ShisenSho => "shisen_sho",
+            Layout::Memory => "memory",
+        }
+    }
+
+    /// Deals the board of `seed`, along with the seed it was eventually dealt from.
+    fn deal(
+        &self,
+        seed: u64,
+    ) -> (Vec<(tile::Position, tile::Variant)>, u64) {
+        let size = tile::PositionGenerator::<tile::Turtle>::TILE_GRID_SIZE as u32;
+        let turtle = || tile::PositionGenerator::<tile::Turtle>::new(UVec2::splat(size));
+
+        match self {
+            Layout::Turtle => generator::generate_solvable_board(turtle().collect(), Some(seed)),
+            Layout::TwinTurtle => {
+                // Below rather than beside, as every row has to be a single run of tiles.
+                let height = turtle()
+                    .map(|position| position.y)
+                    .max()
+                    .unwrap_or_default()
+                    + size;
+                let twin = turtle().map(|position| tile::Position(position.0 + UVec3::Y * height));
+                generator::generate_solvable_board(turtle().chain(twin).collect(), Some(seed))
+            },
+            Layout::ShisenSho => shisen::generate_board(Some(seed)),
+            Layout::Memory => memory::generate_board(Some(seed)),
+        }
+    }
+
+    fn rules(
+        &self,
+        turtle: &'static dyn rules::RuleSet,
+    ) -> &'static dyn rules::RuleSet {
+        match self {
+            Layout::Turtle | Layout::TwinTurtle => turtle,
+            Layout::ShisenSho => &shisen::ShisenSho,
+            Layout::Memory => &memory::Memory,
+        }
+    }
+}
+
+const STRATEGIES: [bot::Strategy; 3] = [
+    bot::Strategy::Random,
+    bot::Strategy::Greedy,
+    bot::Strategy::Lookahead,
+];
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Format {
+    Csv,
+    Json,
+}
+
+struct Options {
+    layouts: Vec<Layout>,
+    strategies: Vec<bot::Strategy>,
+    rules: &'static dyn rules::RuleSet,
+    seed: u64,
+    deals: u64,
+    format: Format,
+}
+
+impl Options {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut options = Options {
+            layouts: Vec::new(),
+            strategies: Vec::new(),
+            rules: rules::RULE_SETS[0],
+            seed: 0,
+            deals: 100,
+            format: Format::Csv,
+        };
+
+        while let Some(arg) = args.next() {
+            if arg == "--help" || arg == "-h" {
+                return Err(USAGE.to_string());
+            }
+
+            let value = args
+                .next()
+                .ok_or_else(|| format!("Missing value of {arg}\n\n{USAGE}"))?;
+            let invalid = || format!("Invalid value of {arg}: {value}\n\n{USAGE}");
+
+            match arg.as_str() {
+                "--layout" => options.layouts.push(
+                    *Layout::ALL
+                        .iter()
+                        .find(|layout| layout.name() == value)
+                        .ok_or_else(invalid)?,
+                ),
+                "--strategy" => options.strategies.push(
+                    *STRATEGIES
+                        .iter()
+                        .find(|strategy| name(strategy.key()) == value)
+                        .ok_or_else(invalid)?,
+                ),
+                "--rules" => {
+                    options.rules = *rules::RULE_SETS
+                        .iter()
+                        .find(|rules| name(rules.key()) == value)
+                        .ok_or_else(invalid)?
+                },
+                "--seed" => options.seed = value.parse().map_err(|_| invalid())?,
+                "--deals" => options.deals = value.parse().map_err(|_| invalid())?,
+                "--format" => {
+                    options.format = match value.as_str() {
+                        "csv" => Format::Csv,
+                        "json" => Format::Json,
+                        _ => return Err(invalid()),
+                    }
+                },
+                _ => return Err(format!("Unknown option {arg}\n\n{USAGE}")),
+            }
+        }
+
+        if options.seed.checked_add(options.deals).is_none() {
+            return Err(format!(
+                "Too many deals from seed {}\n\n{USAGE}",
+                options.seed
+            ));
+        }
+
+        if options.layouts.is_empty() {
+            options.layouts = Layout::ALL.to_vec();
+        }
+
+        if options.strategies.is_empty() {
+            options.strategies = STRATEGIES.to_vec();
+        }
+
+        Ok(options)
+    }
+}
+
+/// Name of a message key without its prefix, e.g. "greedy" of "autoplay.greedy".
+fn name(key: &str) -> &str {
+    key.rsplit('.').next().unwrap_or(key)
+}
+
+/// Results of playing every deal of a layout with one strategy.
+#[derive(Serialize, Debug)]
+struct Summary {
+    layout: &'static str,
+    rules: &'static str,
+    strategy: &'static str,
+    deals: u64,
+    solved: u64,
+    /// Fraction of the deals which were cleared.
+    solvability: f64,
+    /// Pairs removed before running out of moves, on average over the deals which were not cleared.
+    dead_end_depth: Option<f64>,
+    /// Time taken to deal a board, on average.
+    generation_ms: f64,
+    /// Times a generator fell back to the next seed, in total.
+    retries: u64,
+}
+
+/// Pairs removed from `board` by `strategy` until it is cleared or no pair is left. Follows the plan
+/// of [bot::Strategy::Lookahead] like the game does, rather than searching anew on every move.
+fn play(
+    mut board: Vec<(tile::Position, tile::Variant)>,
+    rules: &dyn rules::RuleSet,
+    strategy: bot::Strategy,
+    rng: &mut StdRng,
+) -> (usize, bool) {
+    let mut removed = 0;
+    let mut plan: Vec<(tile::Position, tile::Position)> = Vec::new();
+
+    loop {
+        let find =
+            |position: tile::Position| board.iter().position(|(other, _)| *other == position);
+        let planned = plan.pop().and_then(|(a, b)| Some((find(a)?, find(b)?)));
+
+        let pair = match planned {
+            Some(pair) => Some(pair),
+            None if strategy == bot::Strategy::Lookahead => {
+                match bot::solve(&board, rules, bot::BUDGET) {
+                    Some(solution) => {
+                        plan = solution
+                            .into_iter()
+                            .rev()
+                            .map(|(a, b)| (board[a].0, board[b].0))
+                            .collect();
+                        plan.pop().and_then(|(a, b)| Some((find(a)?, find(b)?)))
+                    },
+                    None => bot::Strategy::Greedy.choose(&board, rules, rng),
+                }
+            },
+            None => strategy.choose(&board, rules, rng),
+        };
+
+        let Some((a, b)) = pair else {
+            return (removed, board.is_empty());
+        };
+
+        board.remove(a.max(b));
+        board.remove(a.min(b));
+        removed += 1;
+    }
+}
+
+fn simulate(options: &Options) -> Vec<Summary> {
+    let mut summaries = Vec::new();
+
+    for layout in &options.layouts {
+        let rules = layout.rules(options.rules);
+        let mut deals = Vec::new();
+        let mut generation = Duration::ZERO;
+        let mut retries = 0;
+
+        for seed in options.seed..options.seed + options.deals {
+            let start = Instant::now();
+            let (board, dealt) = layout.deal(seed);
+            generation += start.elapsed();
+            retries += dealt.wrapping_sub(seed);
+            deals.push((seed, board));
+        }
+
+        for strategy in &options.strategies {
+            let mut solved = 0;
+            let mut dead_ends = Vec::new();
+
+            for (seed, board) in &deals {
+                let mut rng = StdRng::seed_from_u64(*seed);
+                let (removed, cleared) = play(board.clone(), rules, *strategy, &mut rng);
+
+                if cleared {
+                    solved += 1;
+                } else {
+                    dead_ends.push(removed);
+                }
+            }
+
+            let count = options.deals.max(1) as f64;
+
+            summaries.push(Summary {
+                layout: layout.name(),
+                rules: name(rules.key()),
+                strategy: name(strategy.key()),
+                deals: options.deals,
+                solved,
+                solvability: solved as f64 / count,
+                dead_end_depth: (!dead_ends.is_empty())
+                    .then(|| dead_ends.iter().sum::<usize>() as f64 / dead_ends.len() as f64),
+                generation_ms: generation.as_secs_f64() * 1000.0 / count,
+                retries,
+            });
+        }
+    }
+
+    summaries
+}
+
+fn csv(summaries: &[Summary]) -> String {
+    let mut csv = String::from(
+        "layout,rules,strategy,deals,solved,solvability,dead_end_depth,generation_ms,retries\n",
+    );
+
+    for summary in summaries {
+        let _ = writeln!(
+            csv,
+            "{},{},{},{},{},{:.4},{},{:.3},{}",
+            summary.layout,
+            summary.rules,
+            summary.strategy,
+            summary.deals,
+            summary.solved,
+            summary.solvability,
+            summary
+                .dead_end_depth
+                .map(|depth| format!("{depth:.2}"))
+                .unwrap_or_default(),
+            summary.generation_ms,
+            summary.retries,
+        );
+    }
+
+    csv
+}
+
+/// Entry point of `mahjong-sim`, see [USAGE].
+pub fn main() {
+    let options = match Options::parse(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{message}");
+            std::process::exit(if message == USAGE { 0 } else { 2 });
+        },
+    };
+
+    let summaries = simulate(&options);
+
+    match options.format {
+        Format::Csv => print!("{}", csv(&summaries)),
+        Format::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&summaries).expect("Summaries are always serializable!")
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Options, String> {
+        Options::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn parse_defaults() {
+        let options = parse(&[]).unwrap();
+
+        assert_eq!(options.layouts, Layout::ALL);
+        assert_eq!(options.strategies, STRATEGIES);
+        assert_eq!(options.rules.key(), rules::RULE_SETS[0].key());
+        assert_eq!((options.seed, options.deals), (0, 100));
+        assert!(matches!(options.format, Format::Csv));
+    }
+
+    #[test]
+    fn parse_options() {
+        let options = parse(&[
+            "--layout",
+            "memory",
+            "--layout",
+            "turtle",
+            "--strategy",
+            "greedy",
+            "--rules",
+            "peek",
+            "--seed",
+            "7",
+            "--deals",
+            "3",
+            "--format",
+            "json",
+        ])
+        .unwrap();
+
+        assert_eq!(options.layouts, [Layout::Memory, Layout::Turtle]);
+        assert_eq!(options.strategies, [bot::Strategy::Greedy]);
+        assert_eq!(options.rules.key(), "rules.peek");
+        assert_eq!((options.seed, options.deals), (7, 3));
+        assert!(matches!(options.format, Format::Json));
+    }
+
+    #[test]
+    fn parse_errors() {
+        for args in [
+            &["--layout", "pyramid"][..],
+            &["--deals", "many"],
+            &["--seed"],
+            &["--fast", "yes"],
+            &["--seed", &u64::MAX.to_string()],
+        ] {
+            assert!(parse(args).is_err(), "{args:?}");
+        }
+
+        assert!(parse(&["--seed", &(u64::MAX - 1).to_string(), "--deals", "1"]).is_ok());
+    }
+}